@@ -15,10 +15,33 @@ pub const JAPAN_LV1: &[u64] = &[
     3927, 3926, 3841, 3831, 3824, 3823, 3741, 3725, 3724, 3653, 3641, 3631, 3624, 3623, 3622, 3036,
 ];
 
+/// 日本の陸地を表す1次メッシュコード一覧を取得する。 `JAPAN_LV1` と同じです。
+///
+/// 並び順は `JAPAN_LV1` と同じく、メッシュコードの降順です。
+/// 海域のみの1次メッシュを除いてグリッドを生成する場合などに使えます。
+pub fn valid_lv1_codes() -> &'static [u64] {
+    JAPAN_LV1
+}
+
+/// 1次メッシュコードが日本の陸地を含む（`JAPAN_LV1` にある）かどうかを確認する。
+///
+/// 1次メッシュコード以外の値の場合は false を返します。
+pub fn is_land_lv1(code: u64) -> bool {
+    // JAPAN_LV1 is sorted in descending order
+    JAPAN_LV1.binary_search_by(|probe| code.cmp(probe)).is_ok()
+}
+
+/// `JAPAN_LV1` の1次メッシュをすべて含む緯度経度の範囲
+///
+/// (南端の緯度, 西端の経度, 北端の緯度, 東端の経度) の順です。
+pub const JAPAN_BBOX: (f64, f64, f64, f64) = (20.0, 122.0, 46.0, 154.0);
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{MeshLevel, to_meshlevel};
+    #[cfg(not(feature = "std"))]
+    use alloc::vec;
 
     #[test]
     fn test_japan_lv1() {
@@ -27,4 +50,34 @@ mod tests {
         assert_eq!(JAPAN_LV1[175], 3036);
         assert_eq!(to_meshlevel(JAPAN_LV1).unwrap(), vec![MeshLevel::Lv1; 176]);
     }
+
+    #[test]
+    fn test_japan_bbox_covers_lv1() {
+        let (lat_s, lon_w, lat_n, lon_e) = JAPAN_BBOX;
+        for &code in JAPAN_LV1 {
+            let (lat, lon) = crate::MeshCode::try_from(code)
+                .unwrap()
+                .point(0.5, 0.5)
+                .unwrap();
+            assert!(lat_s <= lat && lat < lat_n, "{}", code);
+            assert!(lon_w <= lon && lon < lon_e, "{}", code);
+        }
+    }
+
+    #[test]
+    fn test_is_land_lv1() {
+        assert_eq!(valid_lv1_codes(), JAPAN_LV1);
+        assert!(JAPAN_LV1.windows(2).all(|w| w[0] > w[1]));
+        for &code in valid_lv1_codes() {
+            assert!(is_land_lv1(code), "{}", code);
+        }
+
+        // Tokyo
+        assert!(is_land_lv1(5339));
+        // The Pacific Ocean, south-east of Honshu
+        assert!(!is_land_lv1(4545));
+        // Not Lv1 codes
+        assert!(!is_land_lv1(533935));
+        assert!(!is_land_lv1(0));
+    }
 }
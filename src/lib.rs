@@ -1,10 +1,39 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(all(not(feature = "std"), not(feature = "libm")))]
+compile_error!("jismesh needs either the `std` or the `libm` feature for floating point math");
+
 /// メッシュコード一覧
 pub mod codes;
 pub(crate) mod utils;
 pub use utils::{
-    JismeshError, MeshCode, MeshLevel, to_envelope, to_intersects, to_meshcode, to_meshlevel,
-    to_meshpoint,
+    BoundaryRule, Corner, Direction, JismeshError, Mesh100m, MeshCode, MeshLevel, Quadrant,
+    SizeUnit, align_bbox, bounding_envelope, envelope_sample, expand_envelope, is_in_valid_range,
+    iter_in_bbox, level_of, pack_coverage, render_grid, symmetric_difference, to_centroid_arrays,
+    to_envelope, to_intersects, to_intersects_with_margin, to_meshcode, to_meshcode_pairs,
+    to_meshcode_with_boundary, to_meshlevel, to_meshlevel_lenient, to_meshpoint, to_raster_indices,
+    unpack_coverage,
 };
+#[cfg(feature = "std")]
+pub use utils::{
+    MeshSummary, cover_circle, cover_line, cover_polygon, coverage_error, enforce_k_anonymity,
+    level_histogram, summarize,
+};
+
+#[cfg(feature = "rstar")]
+pub use utils::RTreeMeshCode;
+#[cfg(feature = "geojson")]
+pub use utils::to_geojson;
+#[cfg(feature = "rayon")]
+pub use utils::to_meshcode_par;
+#[cfg(feature = "datum")]
+pub use utils::to_meshcode_tokyo_datum;
+#[cfg(feature = "geopackage")]
+pub use utils::write_geopackage;
+#[cfg(feature = "polars")]
+pub use utils::{meshcode_series, meshpoint_series};
 
 #[doc = include_str!("../README.md")]
 #[cfg(doctest)]
@@ -0,0 +1,145 @@
+use super::*;
+use std::collections::{HashMap, HashSet};
+
+/// Coarsens mesh code counts until every aggregated mesh code has a count of at least `k`,
+/// a common statistical disclosure control step.
+///
+/// Mesh codes below the threshold are repeatedly merged into their parent
+/// (`MeshCode::parent`), finest level first. When a parent is formed, every finer mesh code
+/// inside it is merged too, so the result never contains overlapping mesh codes. Merging
+/// stops at Lv1, so Lv1 mesh codes may still be below `k`. The total count is preserved.
+///
+/// Only mesh codes that nest into the parent level (`MeshCode::can_nest_into`) are merged,
+/// so counts are never split across parents.
+///
+/// # Arguments
+/// * `counts` - Counts per mesh code. Levels may be mixed, but mesh codes must not overlap.
+/// * `k` - Minimum count of each aggregated mesh code
+///
+/// # Returns
+/// * `HashMap<MeshCode, u64>` - Counts per aggregated mesh code
+///
+/// # Errors
+/// * Returns an error if the parent of a mesh code can't be calculated (e.g. 538000, whose
+///   longitude digits are out of range)
+/// * Returns an error if a mesh code that doesn't nest into the parent level overlaps a parent
+///   to be formed (e.g. an X4 mesh code across the border of a Lv2 parent)
+pub fn enforce_k_anonymity(
+    counts: &HashMap<MeshCode, u64>,
+    k: u64,
+) -> Result<HashMap<MeshCode, u64>> {
+    let mut counts = counts.clone();
+
+    loop {
+        // The finest level that still has mesh codes below the threshold
+        let Some(level) = counts
+            .iter()
+            .filter(|(code, count)| **count < k && code.level != MeshLevel::Lv1)
+            .map(|(code, _)| code.level)
+            .min_by(|a, b| unit_lat(*a).total_cmp(&unit_lat(*b)))
+        else {
+            return Ok(counts);
+        };
+
+        let parents: HashSet<MeshCode> = counts
+            .iter()
+            .filter(|(code, count)| **count < k && code.level == level)
+            .filter_map(|(code, _)| code.parent().transpose())
+            .collect::<Result<_>>()?;
+        // a level other than Lv1 always has a parent level
+        let parent_level = level.direct_parent().unwrap();
+
+        let mut merged = Vec::new();
+        for code in counts.keys() {
+            if unit_lat(code.level) >= unit_lat(parent_level) {
+                continue;
+            }
+            if !code.can_nest_into(parent_level) {
+                if parents.iter().any(|parent| parent.intersects(code)) {
+                    return Err(JismeshError::UnsupportedMeshLevelConversion(
+                        code.level,
+                        parent_level,
+                    ));
+                }
+                continue;
+            }
+            let parent = code.clamp_to_level(parent_level)?;
+            if parents.contains(&parent) {
+                merged.push((*code, parent));
+            }
+        }
+        for (code, parent) in merged {
+            let count = counts.remove(&code).unwrap_or(0);
+            *counts.entry(parent).or_insert(0) += count;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counts(values: &[(u64, u64)]) -> HashMap<MeshCode, u64> {
+        values
+            .iter()
+            .map(|&(value, count)| (MeshCode::try_from(value).unwrap(), count))
+            .collect()
+    }
+
+    #[test]
+    fn test_enforce_k_anonymity() {
+        let input = counts(&[
+            // Two sparse Lv3 mesh codes in 533935, which merge into 7
+            (53393599, 3),
+            (53393598, 4),
+            // A Lv3 mesh code that already meets k
+            (53394500, 10),
+            // A lone Lv3 mesh code that collapses all the way to Lv1
+            (53400001, 1),
+        ]);
+        let result = enforce_k_anonymity(&input, 5).unwrap();
+        assert_eq!(result, counts(&[(533935, 7), (53394500, 10), (5340, 1)]));
+        assert_eq!(result.values().sum::<u64>(), input.values().sum::<u64>());
+    }
+
+    #[test]
+    fn test_enforce_k_anonymity_merges_finer_codes() {
+        // When 533935 is formed, every finer mesh code inside it is merged as well,
+        // even those that already meet k
+        let input = counts(&[
+            (53393599, 1),
+            (533935001, 20),
+            (53393510, 10),
+            (53400001, 1),
+        ]);
+        let result = enforce_k_anonymity(&input, 5).unwrap();
+        assert_eq!(result, counts(&[(533935, 31), (5340, 1)]));
+    }
+
+    #[test]
+    fn test_enforce_k_anonymity_non_nesting() {
+        // The X4 mesh code 533937637 straddles the eastern border of 533935, so it can't be
+        // merged into it
+        let input = counts(&[(53393599, 1), (533937637, 10)]);
+        assert_eq!(
+            enforce_k_anonymity(&input, 5),
+            Err(JismeshError::UnsupportedMeshLevelConversion(
+                MeshLevel::X4,
+                MeshLevel::Lv2
+            ))
+        );
+
+        // Non-nesting mesh codes away from the merged parents are kept as they are
+        let input = counts(&[(53393599, 1), (53393598, 4), (533923617, 10)]);
+        let result = enforce_k_anonymity(&input, 5).unwrap();
+        assert_eq!(result, counts(&[(533935, 5), (533923617, 10)]));
+    }
+
+    #[test]
+    fn test_enforce_k_anonymity_undecodable() {
+        // Merging 5380005 into its X40 parent also clamps 538000 to X40, which fails as the
+        // longitude digits of 538000 are out of range
+        let input = counts(&[(5380005, 1), (538000, 10)]);
+        assert!(enforce_k_anonymity(&input, 5).is_err());
+    }
+}
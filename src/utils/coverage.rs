@@ -0,0 +1,215 @@
+use super::meshset::common_level;
+use super::*;
+use alloc::collections::{BTreeMap, BTreeSet};
+
+fn write_varint(buf: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        buf.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+fn read_varint(data: &[u8], pos: &mut usize) -> Result<u64> {
+    let mut value = 0u64;
+    let mut shift = 0;
+    loop {
+        let byte = *data
+            .get(*pos)
+            .ok_or_else(|| JismeshError::InvalidCoverageData("unexpected end of data".into()))?;
+        *pos += 1;
+        if shift >= 64 {
+            return Err(JismeshError::InvalidCoverageData("varint overflow".into()));
+        }
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+        shift += 7;
+    }
+}
+
+/// Packs a set of same-level mesh codes into a compact run-length binary format.
+///
+/// The cells are grouped by row of their `global_index`, and each row stores the
+/// runs of consecutive present columns. All integers are LEB128 varints:
+///
+/// ```text
+/// level, row_count,
+///   (row_delta, run_count, (col_gap, run_length) * run_count) * row_count
+/// ```
+///
+/// Duplicate mesh codes are stored once. An empty input packs into an empty buffer.
+///
+/// # Errors
+/// * Returns an error if the mesh codes are not all at the same level
+pub fn pack_coverage(codes: &[MeshCode]) -> Result<Vec<u8>> {
+    let Some(level) = common_level(codes)? else {
+        return Ok(Vec::new());
+    };
+
+    let mut rows: BTreeMap<u64, BTreeSet<u64>> = BTreeMap::new();
+    for code in codes {
+        let (row, col) = code.global_index();
+        rows.entry(row).or_default().insert(col);
+    }
+
+    let mut buf = Vec::new();
+    write_varint(&mut buf, level as u64);
+    write_varint(&mut buf, rows.len() as u64);
+
+    let mut prev_row = 0;
+    for (row, cols) in rows {
+        // Collect runs of consecutive columns as (start, length)
+        let mut runs: Vec<(u64, u64)> = Vec::new();
+        for col in cols {
+            match runs.last_mut() {
+                Some((start, length)) if *start + *length == col => *length += 1,
+                _ => runs.push((col, 1)),
+            }
+        }
+
+        write_varint(&mut buf, row - prev_row);
+        write_varint(&mut buf, runs.len() as u64);
+        let mut prev_end = 0;
+        for (start, length) in runs {
+            write_varint(&mut buf, start - prev_end);
+            write_varint(&mut buf, length);
+            prev_end = start + length;
+        }
+        prev_row = row;
+    }
+
+    Ok(buf)
+}
+
+/// Unpacks mesh codes from the format produced by `pack_coverage`.
+///
+/// The mesh codes are returned in row-major order, from south-west to north-east.
+///
+/// # Errors
+/// * Returns an error if the data is truncated or malformed
+pub fn unpack_coverage(data: &[u8]) -> Result<Vec<MeshCode>> {
+    if data.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut pos = 0;
+    let level = read_varint(data, &mut pos)?;
+    let level = MeshLevel::try_from(level as usize)?;
+    let row_count = read_varint(data, &mut pos)?;
+
+    let mut codes = Vec::new();
+    let mut row: u64 = 0;
+    for _ in 0..row_count {
+        row = row
+            .checked_add(read_varint(data, &mut pos)?)
+            .ok_or_else(|| JismeshError::InvalidCoverageData("row index overflow".into()))?;
+        let run_count = read_varint(data, &mut pos)?;
+        let mut col: u64 = 0;
+        for _ in 0..run_count {
+            col = col
+                .checked_add(read_varint(data, &mut pos)?)
+                .ok_or_else(|| JismeshError::InvalidCoverageData("column index overflow".into()))?;
+            let length = read_varint(data, &mut pos)?;
+            for _ in 0..length {
+                codes.push(MeshCode::from_global_index(row, col, level)?);
+                col += 1;
+            }
+        }
+    }
+
+    if pos != data.len() {
+        return Err(JismeshError::InvalidCoverageData(
+            "trailing bytes after coverage data".into(),
+        ));
+    }
+
+    Ok(codes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_coverage_round_trip() {
+        // 10x10 block of Lv3 meshes (exactly the Lv2 mesh 533935)
+        let mut codes = Vec::new();
+        for g in 0..10 {
+            for h in 0..10 {
+                codes.push(MeshCode::try_from(53393500 + g * 10 + h).unwrap());
+            }
+        }
+
+        let packed = pack_coverage(&codes).unwrap();
+        assert!(packed.len() < codes.len() * 8 / 10);
+
+        let mut unpacked = unpack_coverage(&packed).unwrap();
+        unpacked.sort();
+        codes.sort();
+        assert_eq!(unpacked, codes);
+    }
+
+    #[test]
+    fn test_pack_coverage_sparse() {
+        let codes: Vec<MeshCode> = [53393599, 53393590, 53394509, 52353680]
+            .iter()
+            .map(|&v| MeshCode::try_from(v).unwrap())
+            .collect();
+
+        let mut unpacked = unpack_coverage(&pack_coverage(&codes).unwrap()).unwrap();
+        unpacked.sort();
+        let mut expected = codes.clone();
+        expected.sort();
+        assert_eq!(unpacked, expected);
+    }
+
+    #[test]
+    fn test_pack_coverage_empty() {
+        assert_eq!(pack_coverage(&[]).unwrap(), Vec::<u8>::new());
+        assert!(unpack_coverage(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_unpack_coverage_invalid() {
+        let codes = vec![MeshCode::try_from(53393599).unwrap()];
+        let packed = pack_coverage(&codes).unwrap();
+        assert!(unpack_coverage(&packed[..packed.len() - 1]).is_err());
+
+        let mismatched = vec![
+            MeshCode::try_from(53393599).unwrap(),
+            MeshCode::try_from(533935).unwrap(),
+        ];
+        assert!(pack_coverage(&mismatched).is_err());
+    }
+
+    #[test]
+    fn test_unpack_coverage_index_overflow() {
+        let level = pack_coverage(&[MeshCode::try_from(53393599).unwrap()]).unwrap()[0];
+
+        // Two empty rows whose deltas add up past u64::MAX
+        let mut data = vec![level];
+        for value in [2, 1, 0, u64::MAX, 0] {
+            write_varint(&mut data, value);
+        }
+        assert_eq!(
+            unpack_coverage(&data),
+            Err(JismeshError::InvalidCoverageData(
+                "row index overflow".into()
+            ))
+        );
+
+        // Two empty runs whose gaps add up past u64::MAX
+        let mut data = vec![level];
+        for value in [1, 0, 2, u64::MAX, 0, 1, 0] {
+            write_varint(&mut data, value);
+        }
+        assert_eq!(
+            unpack_coverage(&data),
+            Err(JismeshError::InvalidCoverageData(
+                "column index overflow".into()
+            ))
+        );
+    }
+}
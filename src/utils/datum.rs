@@ -0,0 +1,203 @@
+use super::meshcode::validate_latlng;
+use super::*;
+
+/// Bessel 1841 ellipsoid (Tokyo Datum) semi-major axis in meters
+const BESSEL_A: f64 = 6_377_397.155;
+/// Bessel 1841 flattening
+const BESSEL_F: f64 = 1.0 / 299.152_812_8;
+/// GRS80 ellipsoid (JGD2000 / JGD2011) semi-major axis in meters
+const GRS80_A: f64 = 6_378_137.0;
+/// GRS80 flattening
+const GRS80_F: f64 = 1.0 / 298.257_222_101;
+/// Geocentric translation (X, Y, Z) in meters from the Tokyo Datum to JGD2000, as published by
+/// the Geospatial Information Authority of Japan.
+const TOKYO_TO_JGD: (f64, f64, f64) = (-146.414, 507.337, 680.507);
+
+/// Converts a geodetic position on the ellipsoid surface to geocentric (X, Y, Z) coordinates.
+fn to_geocentric(lat: f64, lon: f64, a: f64, f: f64) -> (f64, f64, f64) {
+    let e2 = f * (2.0 - f);
+    let (phi, lambda) = (lat.to_radians(), lon.to_radians());
+    let n = a / (1.0 - e2 * phi.sin() * phi.sin()).sqrt();
+    (
+        n * phi.cos() * lambda.cos(),
+        n * phi.cos() * lambda.sin(),
+        n * (1.0 - e2) * phi.sin(),
+    )
+}
+
+/// Converts geocentric (X, Y, Z) coordinates to a geodetic latitude / longitude, ignoring the
+/// height. The latitude is refined iteratively, which converges to well below a micrometer.
+fn to_geodetic(x: f64, y: f64, z: f64, a: f64, f: f64) -> (f64, f64) {
+    let e2 = f * (2.0 - f);
+    let p = (x * x + y * y).sqrt();
+    let mut phi = z.atan2(p * (1.0 - e2));
+    for _ in 0..5 {
+        let n = a / (1.0 - e2 * phi.sin() * phi.sin()).sqrt();
+        phi = (z + e2 * n * phi.sin()).atan2(p);
+    }
+    (phi.to_degrees(), y.atan2(x).to_degrees())
+}
+
+/// Converts a Tokyo Datum latitude / longitude to JGD2000, with a 3 parameter geocentric
+/// translation between the Bessel 1841 and GRS80 ellipsoids.
+pub(crate) fn tokyo_to_jgd(lat: f64, lon: f64) -> (f64, f64) {
+    let (x, y, z) = to_geocentric(lat, lon, BESSEL_A, BESSEL_F);
+    let (dx, dy, dz) = TOKYO_TO_JGD;
+    to_geodetic(x + dx, y + dy, z + dz, GRS80_A, GRS80_F)
+}
+
+/// Converts a JGD2000 latitude / longitude to the Tokyo Datum. The inverse of `tokyo_to_jgd`.
+pub(crate) fn jgd_to_tokyo(lat: f64, lon: f64) -> (f64, f64) {
+    let (x, y, z) = to_geocentric(lat, lon, GRS80_A, GRS80_F);
+    let (dx, dy, dz) = TOKYO_TO_JGD;
+    to_geodetic(x - dx, y - dy, z - dz, BESSEL_A, BESSEL_F)
+}
+
+/// Converts latitude & longitude on the old Tokyo Datum (日本測地系) to mesh codes.
+/// 日本測地系の緯度経度を世界測地系に変換してから、指定次の地域メッシュコードを算出する。
+///
+/// The coordinates are converted to JGD2000 with the 3 parameter geocentric translation
+/// (ΔX = -146.414 m, ΔY = +507.337 m, ΔZ = +680.507 m) published by the Geospatial Information
+/// Authority of Japan. This is accurate to about 1 m around Tokyo, but can be off by several
+/// meters elsewhere, because the Tokyo Datum is distorted locally. Use the official grid based
+/// conversion (TKY2JGD) when the result must be exact near a mesh boundary.
+///
+/// # Arguments
+/// * `lat` - Latitudes on the Tokyo Datum
+/// * `lon` - Longitudes on the Tokyo Datum
+/// * `level` - Mesh level
+///
+/// # Errors
+/// * Returns an error if a Tokyo Datum coordinate is out of bounds, reporting the input value
+/// * Returns an error if a converted coordinate is out of bounds
+pub fn to_meshcode_tokyo_datum(
+    lat: &[f64],
+    lon: &[f64],
+    level: MeshLevel,
+) -> Result<Vec<MeshCode>> {
+    if lat.is_empty() || lon.is_empty() {
+        return to_meshcode(lat, lon, level);
+    }
+    validate_latlng(lat, lon)?;
+    let result_len = lat.len().max(lon.len());
+    let (lat_jgd, lon_jgd): (Vec<f64>, Vec<f64>) = (0..result_len)
+        .map(|i| tokyo_to_jgd(lat[i % lat.len()], lon[i % lon.len()]))
+        .unzip();
+    to_meshcode(&lat_jgd, &lon_jgd, level)
+}
+
+impl MeshCode {
+    /// `point` と同じですが、座標を日本測地系（旧測地系）に変換して返します。
+    /// 返却値は (緯度, 経度) です。
+    ///
+    /// 変換は `to_meshcode_tokyo_datum` と同じ 3 パラメータの変換の逆変換で、
+    /// 東京付近では 1 m 程度、その他の地域では数 m の誤差があります。
+    pub fn point_tokyo_datum(
+        &self,
+        lat_multiplier: f64,
+        lon_multiplier: f64,
+    ) -> Result<(f64, f64)> {
+        let (lat, lon) = self.point(lat_multiplier, lon_multiplier)?;
+        Ok(jgd_to_tokyo(lat, lon))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn dms(d: f64, m: f64, s: f64) -> f64 {
+        d + m / 60.0 + s / 3600.0
+    }
+
+    /// The Japanese geodetic datum origin (日本経緯度原点), published by GSI on both datums.
+    fn datum_origin() -> ((f64, f64), (f64, f64)) {
+        (
+            (dms(35.0, 39.0, 17.5148), dms(139.0, 44.0, 40.5020)),
+            (dms(35.0, 39.0, 29.1572), dms(139.0, 44.0, 28.8759)),
+        )
+    }
+
+    #[test]
+    fn test_tokyo_to_jgd() {
+        let ((lat_t, lon_t), (lat_j, lon_j)) = datum_origin();
+        let (lat, lon) = tokyo_to_jgd(lat_t, lon_t);
+        // Within 1 m (about 1e-5 degrees) of the published JGD2000 position
+        assert_relative_eq!(lat, lat_j, epsilon = 1e-5);
+        assert_relative_eq!(lon, lon_j, epsilon = 1e-5);
+
+        let (lat, lon) = jgd_to_tokyo(lat_j, lon_j);
+        assert_relative_eq!(lat, lat_t, epsilon = 1e-5);
+        assert_relative_eq!(lon, lon_t, epsilon = 1e-5);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for (lat, lon) in [
+            (35.0, 139.0),
+            (43.06, 141.35),
+            (26.21, 127.68),
+            (33.59, 130.4),
+        ] {
+            let (lat_t, lon_t) = jgd_to_tokyo(lat, lon);
+            let (lat_j, lon_j) = tokyo_to_jgd(lat_t, lon_t);
+            // Not exact, as the ellipsoidal height changes between the datums, but within 1 cm
+            assert_relative_eq!(lat_j, lat, epsilon = 1e-7);
+            assert_relative_eq!(lon_j, lon, epsilon = 1e-7);
+            // The shift is several hundred meters everywhere in Japan
+            assert!((lat_t - lat).abs() > 0.001 || (lon_t - lon).abs() > 0.001);
+        }
+    }
+
+    #[test]
+    fn test_to_meshcode_tokyo_datum() {
+        let ((lat_t, lon_t), (lat_j, lon_j)) = datum_origin();
+        let expected = to_meshcode(&[lat_j], &[lon_j], MeshLevel::Lv5).unwrap();
+        assert_eq!(
+            to_meshcode_tokyo_datum(&[lat_t], &[lon_t], MeshLevel::Lv5).unwrap(),
+            expected
+        );
+        // Without the conversion, the point falls into a different mesh
+        assert_ne!(
+            to_meshcode(&[lat_t], &[lon_t], MeshLevel::Lv5).unwrap(),
+            expected
+        );
+
+        // Broadcasts like to_meshcode
+        let result = to_meshcode_tokyo_datum(&[lat_t], &[lon_t, lon_t], MeshLevel::Lv3).unwrap();
+        assert_eq!(result.len(), 2);
+
+        assert!(
+            to_meshcode_tokyo_datum(&[], &[], MeshLevel::Lv3)
+                .unwrap()
+                .is_empty()
+        );
+        assert!(to_meshcode_tokyo_datum(&[-1.0], &[139.0], MeshLevel::Lv3).is_err());
+
+        // Out of bounds inputs are reported as given, not after the conversion
+        assert_eq!(
+            to_meshcode_tokyo_datum(&[35.0, 70.0], &[139.0], MeshLevel::Lv3),
+            Err(JismeshError::LatitudeOutOfBounds {
+                value: 70.0,
+                index: 1,
+            })
+        );
+        assert_eq!(
+            to_meshcode_tokyo_datum(&[35.0], &[181.0], MeshLevel::Lv3),
+            Err(JismeshError::LongitudeOutOfBounds {
+                value: 181.0,
+                index: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn test_point_tokyo_datum() {
+        let meshcode = to_meshcode_tokyo_datum(&[35.5], &[139.5], MeshLevel::Lv3).unwrap()[0];
+        let (lat_s, lon_w) = meshcode.point_tokyo_datum(0.0, 0.0).unwrap();
+        let (lat_n, lon_e) = meshcode.point_tokyo_datum(1.0, 1.0).unwrap();
+        assert!(lat_s <= 35.5 && 35.5 < lat_n);
+        assert!(lon_w <= 139.5 && 139.5 < lon_e);
+    }
+}
@@ -1,5 +1,8 @@
+use super::grid::{Direction, GridIter};
+use super::meshset::common_level;
+use super::random::SplitMix64;
 use super::*;
-use crate::utils::meshcode::{MeshCode, to_meshcode};
+use crate::utils::meshcode::{MeshCode, validate_bbox_corners};
 
 /// Generate an envelope of mesh codes that cover the rectangular area
 /// defined by the southwest and northeast mesh codes.
@@ -9,11 +12,18 @@ use crate::utils::meshcode::{MeshCode, to_meshcode};
 /// * `meshcode_ne` - Northeast mesh code
 ///
 /// # Returns
-/// * `Result<Vec<MeshCode>>` - Vector of mesh codes that cover the area
+/// * `Result<Vec<MeshCode>>` - Vector of mesh codes that cover the area, in row-major order
+///   from south-west and without duplicates
 ///
 /// # Errors
 /// * Returns an error if the mesh levels of the input codes don't match
+/// * Returns an error if `meshcode_ne` is south or west of `meshcode_sw`
 pub fn to_envelope(meshcode_sw: &MeshCode, meshcode_ne: &MeshCode) -> Result<Vec<MeshCode>> {
+    Ok(envelope_iter(meshcode_sw, meshcode_ne)?.collect())
+}
+
+/// Validate the corners of an envelope and return an iterator over its mesh codes.
+fn envelope_iter(meshcode_sw: &MeshCode, meshcode_ne: &MeshCode) -> Result<GridIter> {
     // Get mesh levels for both codes
     let level_sw = meshcode_sw.level;
     let level_ne = meshcode_ne.level;
@@ -23,97 +33,276 @@ pub fn to_envelope(meshcode_sw: &MeshCode, meshcode_ne: &MeshCode) -> Result<Vec
         return Err(JismeshError::MismatchedMeshLevels(level_sw, level_ne));
     }
 
-    let margin_lat = 0.5;
-    let margin_lon = 0.5;
-
-    // Generate mesh points for southwest and northeast corners
-    let sw_points = to_meshpoint(&[meshcode_sw.value], &[margin_lat], &[margin_lon])?;
-
-    let ne_points = to_meshpoint(&[meshcode_ne.value], &[1.0], &[1.0])?;
-
-    let lat_s = sw_points[0][0];
-    let lon_w = sw_points[1][0];
-    let lat_n = ne_points[0][0];
-    let lon_e = ne_points[1][0];
-
-    make_envelope(lat_s, lon_w, lat_n, lon_e, level_sw)
+    let sw = meshcode_sw.global_index();
+    let ne = meshcode_ne.global_index();
+    if ne.0 < sw.0 || ne.1 < sw.1 {
+        return Err(JismeshError::InvalidEnvelopeCorners(
+            meshcode_sw.value,
+            meshcode_ne.value,
+        ));
+    }
+    Ok(GridIter::new(level_sw, sw, ne))
 }
 
-/// Generate mesh codes that intersect with the given mesh code at the specified level.
+/// Find the smallest envelope that contains all the given mesh codes.
+///
+/// This is the inverse of `to_envelope`: passing the returned corners to `to_envelope`
+/// generates every mesh code of the bounding rectangle.
 ///
 /// # Arguments
-/// * `meshcode` - Mesh code to find intersections with
-/// * `to_level` - Target mesh level for the intersection
+/// * `codes` - Mesh codes at the same level
 ///
 /// # Returns
-/// * `Result<Vec<MeshCode>>` - Vector of mesh codes that intersect with the input code
-pub fn to_intersects(meshcode: &MeshCode, to_level: MeshLevel) -> Result<Vec<MeshCode>> {
-    // Get mesh level for the input code
-    let from_level = meshcode.level;
-
-    let from_unit_lat = unit_lat(from_level);
-    let from_unit_lon = unit_lon(from_level);
-
-    let to_unit_lat = unit_lat(to_level);
-    let to_unit_lon = unit_lon(to_level);
-
-    // Calculate margins based on the relative unit sizes
-    let margin_lat = if to_unit_lat <= from_unit_lat {
-        (to_unit_lat / from_unit_lat) / 2.0
-    } else {
-        0.5
-    };
-
-    let margin_lon = if to_unit_lon <= from_unit_lon {
-        (to_unit_lon / from_unit_lon) / 2.0
-    } else {
-        0.5
-    };
-
-    // Generate mesh points for the original mesh code
-    let from_points_sw = to_meshpoint(&[meshcode.value], &[margin_lat], &[margin_lon])?;
+/// * `Result<(MeshCode, MeshCode)>` - The southwest and northeast mesh codes of the envelope
+///
+/// # Errors
+/// * Returns an error if `codes` is empty or the mesh codes are not all at the same level
+pub fn bounding_envelope(codes: &[MeshCode]) -> Result<(MeshCode, MeshCode)> {
+    let level = common_level(codes)?.ok_or(JismeshError::EmptyInput)?;
+
+    let (mut row_s, mut col_w) = (u64::MAX, u64::MAX);
+    let (mut row_n, mut col_e) = (0, 0);
+    for code in codes {
+        let (row, col) = code.global_index();
+        row_s = row_s.min(row);
+        row_n = row_n.max(row);
+        col_w = col_w.min(col);
+        col_e = col_e.max(col);
+    }
 
-    let from_points_ne = to_meshpoint(&[meshcode.value], &[1.0], &[1.0])?;
+    Ok((
+        MeshCode::from_global_index(row_s, col_w, level)?,
+        MeshCode::from_global_index(row_n, col_e, level)?,
+    ))
+}
 
-    let from_lat_s = from_points_sw[0][0];
-    let from_lon_w = from_points_sw[1][0];
-    let from_lat_n = from_points_ne[0][0];
-    let from_lon_e = from_points_ne[1][0];
+/// Expand a bounding box outward to whole mesh codes at a level.
+///
+/// The south-west corner is floored and the north-east corner is ceiled to grid lines, so
+/// the envelope of the returned corners fully covers the original bounding box. A north or
+/// east edge that lies exactly on a grid line doesn't add another row or column.
+///
+/// # Arguments
+/// * `lat_s` - Southern latitude
+/// * `lon_w` - Western longitude
+/// * `lat_n` - Northern latitude
+/// * `lon_e` - Eastern longitude
+/// * `level` - Mesh level of the envelope
+///
+/// # Returns
+/// * `Result<(MeshCode, MeshCode)>` - The southwest and northeast mesh codes of the envelope
+///
+/// # Errors
+/// * Returns an error if a corner is out of bounds
+/// * Returns an error if the north-east corner is south or west of the south-west corner
+pub fn align_bbox(
+    lat_s: f64,
+    lon_w: f64,
+    lat_n: f64,
+    lon_e: f64,
+    level: MeshLevel,
+) -> Result<(MeshCode, MeshCode)> {
+    let sw = MeshCode::try_from_latlng(lat_s, lon_w, level)?;
+    MeshCode::try_from_latlng(lat_n, lon_e, level)?;
+    if lat_n < lat_s || lon_e < lon_w {
+        return Err(JismeshError::InvalidBoundingBox(lat_s, lon_w, lat_n, lon_e));
+    }
 
-    make_envelope(from_lat_s, from_lon_w, from_lat_n, from_lon_e, to_level)
+    // A small tolerance keeps edges on a grid line from adding a row or column
+    const EPS: f64 = 1e-9;
+    let (row_s, col_w) = sw.global_index();
+    let row_n = ((lat_n / unit_lat(level) - EPS).ceil() as u64).saturating_sub(1);
+    let col_e = (((lon_e - 100.0) / unit_lon(level) - EPS).ceil() as u64).saturating_sub(1);
+    let ne = MeshCode::from_global_index(row_n.max(row_s), col_e.max(col_w), level)?;
+    Ok((sw, ne))
 }
 
-/// Internal helper function to generate mesh codes within a bounding box
-fn make_envelope(
+/// Lazily iterate over the mesh codes at a level that overlap a bounding box.
+///
+/// The mesh codes are yielded in the same row-major order as `to_envelope`, starting from the
+/// south-west corner and going east, then north. Only meshes overlapping the interior of the
+/// bounding box are included, so a north or east edge that lies exactly on a grid line doesn't
+/// add another row or column.
+///
+/// # Arguments
+/// * `lat_s` - Southern latitude
+/// * `lon_w` - Western longitude
+/// * `lat_n` - Northern latitude
+/// * `lon_e` - Eastern longitude
+/// * `level` - Mesh level of the mesh codes
+///
+/// # Returns
+/// * `Result<impl Iterator<Item = MeshCode>>` - Iterator over the mesh codes in the bounding box
+///
+/// # Errors
+/// * Returns an error if the south-west corner is outside `0 <= lat < 66.66` and
+///   `100 <= lon < 180`, or the north-east corner is outside `0 <= lat <= 66.66` and
+///   `100 <= lon <= 180`. The index of the error is 0 for the south-west corner and 1 for the
+///   north-east corner
+/// * Returns an error if the north-east corner is south or west of the south-west corner
+pub fn iter_in_bbox(
     lat_s: f64,
     lon_w: f64,
     lat_n: f64,
     lon_e: f64,
     level: MeshLevel,
-) -> Result<Vec<MeshCode>> {
-    let to_unit_lat = unit_lat(level);
-    let to_unit_lon = unit_lon(level);
+) -> Result<impl Iterator<Item = MeshCode>> {
+    validate_bbox_corners(lat_s, lon_w, lat_n, lon_e)?;
+    if lat_n < lat_s || lon_e < lon_w {
+        return Err(JismeshError::InvalidBoundingBox(lat_s, lon_w, lat_n, lon_e));
+    }
+    Ok(GridIter::from_bbox(level, lat_s, lon_w, lat_n, lon_e))
+}
 
-    // Calculate how many meshes we need in each direction
-    let lat_count = ((lat_n - lat_s) / to_unit_lat).ceil() as usize;
-    let lon_count = ((lon_e - lon_w) / to_unit_lon).ceil() as usize;
-    let point_count = lat_count * lon_count;
+/// Draw a uniform random sample of mesh codes from the envelope defined by the southwest
+/// and northeast mesh codes, without materializing the whole envelope.
+///
+/// The envelope is walked lazily and sampled with reservoir sampling, so memory use is
+/// bounded by `n`. The same `seed` always gives the same sample.
+///
+/// # Arguments
+/// * `meshcode_sw` - Southwest mesh code
+/// * `meshcode_ne` - Northeast mesh code
+/// * `n` - Maximum number of mesh codes to sample
+/// * `seed` - Seed for the random number generator
+///
+/// # Returns
+/// * `Result<Vec<MeshCode>>` - `min(n, total)` mesh codes from the envelope
+///
+/// # Errors
+/// * Returns an error if the mesh levels of the input codes don't match
+/// * Returns an error if `meshcode_ne` is south or west of `meshcode_sw`
+pub fn envelope_sample(
+    meshcode_sw: &MeshCode,
+    meshcode_ne: &MeshCode,
+    n: usize,
+    seed: u64,
+) -> Result<Vec<MeshCode>> {
+    let iter = envelope_iter(meshcode_sw, meshcode_ne)?;
+    let mut rng = SplitMix64::new(seed);
+    let mut reservoir = Vec::with_capacity(n.min(iter.total() as usize));
+    for (i, code) in iter.enumerate() {
+        if i < n {
+            reservoir.push(code);
+        } else {
+            let j = rng.next_below(i as u64 + 1) as usize;
+            if j < n {
+                reservoir[j] = code;
+            }
+        }
+    }
 
-    let mut lats = Vec::with_capacity(point_count);
-    let mut lons = Vec::with_capacity(point_count);
-    for i in 0..lat_count {
-        let to_lat = lat_s + (i as f64 * to_unit_lat);
+    Ok(reservoir)
+}
 
-        // Generate all longitude points for this latitude
-        for j in 0..lon_count {
-            let to_lon = lon_w + (j as f64 * to_unit_lon);
+/// Expand an existing envelope by one row and/or column of mesh codes in a direction,
+/// returning only the newly added mesh codes.
+///
+/// The new strip is computed from the corners of the current envelope with
+/// `MeshCode::step`, so the existing mesh codes don't have to be regenerated.
+/// Diagonal directions add both a row and a column.
+///
+/// # Arguments
+/// * `current` - Mesh codes of the current envelope
+/// * `direction` - Direction to expand to
+/// * `level` - Mesh level of the envelope
+///
+/// # Returns
+/// * `Result<Vec<MeshCode>>` - The added mesh codes, in row-major order from south-west
+///
+/// # Errors
+/// * Returns an error if `current` is empty or its mesh codes are not all at `level`
+/// * Returns an error if the expanded envelope leaves the valid range
+pub fn expand_envelope(
+    current: &[MeshCode],
+    direction: Direction,
+    level: MeshLevel,
+) -> Result<Vec<MeshCode>> {
+    if current.is_empty() {
+        return Err(JismeshError::EmptyInput);
+    }
 
-            lats.push(to_lat);
-            lons.push(to_lon);
+    let mut sw = (u64::MAX, u64::MAX);
+    let mut ne = (0, 0);
+    for code in current {
+        if code.level != level {
+            return Err(JismeshError::MismatchedMeshLevels(level, code.level));
         }
+        let (row, col) = code.global_index();
+        sw = (sw.0.min(row), sw.1.min(col));
+        ne = (ne.0.max(row), ne.1.max(col));
     }
 
-    to_meshcode(&lats, &lons, level)
+    let (d_row, d_col) = direction.offset();
+    let new_sw = MeshCode::from_global_index(sw.0, sw.1, level)?
+        .step(d_row.min(0), d_col.min(0))?
+        .global_index();
+    let new_ne = MeshCode::from_global_index(ne.0, ne.1, level)?
+        .step(d_row.max(0), d_col.max(0))?
+        .global_index();
+
+    let added = GridIter::new(level, new_sw, new_ne)
+        .filter(|code| {
+            let (row, col) = code.global_index();
+            !(sw.0 <= row && row <= ne.0 && sw.1 <= col && col <= ne.1)
+        })
+        .collect();
+    Ok(added)
+}
+
+/// Generate mesh codes that intersect with the given mesh code at the specified level.
+///
+/// If `to_level` is coarser than the level of `meshcode`, the single mesh code containing
+/// `meshcode` is returned (same as `MeshCode::lower_level`).
+///
+/// # Arguments
+/// * `meshcode` - Mesh code to find intersections with
+/// * `to_level` - Target mesh level for the intersection
+///
+/// # Returns
+/// * `Result<Vec<MeshCode>>` - Vector of mesh codes that intersect with the input code, in
+///   row-major order from south-west and without duplicates
+pub fn to_intersects(meshcode: &MeshCode, to_level: MeshLevel) -> Result<Vec<MeshCode>> {
+    Ok(meshcode.intersects_iter(to_level)?.collect())
+}
+
+/// Generate mesh codes at the specified level that intersect with the given mesh code
+/// expanded by a margin on every side.
+///
+/// The margin is measured in cells of `to_level`. With a margin of `0.0` the result is the
+/// same as `to_intersects`: only mesh codes overlapping the interior of `meshcode` are
+/// returned. Any positive margin also includes mesh codes that merely touch the edge, and a
+/// margin of `1.0` adds a full ring of neighbouring mesh codes. Unlike `to_intersects`, a
+/// coarser `to_level` may return several mesh codes if the expanded area crosses their
+/// boundaries.
+///
+/// # Arguments
+/// * `meshcode` - Mesh code to find intersections with
+/// * `to_level` - Target mesh level for the intersection
+/// * `margin` - Expansion on each side, in cells of `to_level`
+///
+/// # Returns
+/// * `Result<Vec<MeshCode>>` - Mesh codes in row-major order from south-west, clipped to
+///   the valid range
+///
+/// # Errors
+/// * Returns an error if `margin` is negative, NaN or infinite
+pub fn to_intersects_with_margin(
+    meshcode: &MeshCode,
+    to_level: MeshLevel,
+    margin: f64,
+) -> Result<Vec<MeshCode>> {
+    if !margin.is_finite() || margin < 0.0 {
+        return Err(JismeshError::InvalidMargin(margin));
+    }
+    let margin_lat = margin * unit_lat(to_level);
+    let margin_lon = margin * unit_lon(to_level);
+
+    let (lat_s, lon_w) = meshcode.point(0.0, 0.0)?;
+    let (lat_n, lon_e) = meshcode.point(1.0, 1.0)?;
+    let (lat_s, lat_n) = (lat_s - margin_lat, lat_n + margin_lat);
+    let (lon_w, lon_e) = (lon_w - margin_lon, lon_e + margin_lon);
+    Ok(GridIter::from_bbox(to_level, lat_s, lon_w, lat_n, lon_e).collect())
 }
 
 #[cfg(test)]
@@ -150,6 +339,32 @@ mod tests {
         assert!(result.iter().any(|&x| x == 58405449));
     }
 
+    #[test]
+    fn test_to_envelope_same_cell_all_levels() {
+        use strum::IntoEnumIterator;
+
+        // Include points on grid lines and just below Lv1 / Lv2 boundaries
+        let points = [
+            (35.658581, 139.745433),
+            (34.987574, 135.759363),
+            (36.0, 140.0),
+            (35.999999, 139.999999),
+            (35.0 + 1.0 / 12.0, 139.125),
+        ];
+        for level in MeshLevel::iter() {
+            for (lat, lon) in points {
+                let meshcode = MeshCode::try_from_latlng(lat, lon, level).unwrap();
+                assert_eq!(
+                    to_envelope(&meshcode, &meshcode).unwrap(),
+                    vec![meshcode],
+                    "Failed for {} at {}",
+                    meshcode,
+                    level
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_to_intersects() {
         // Test conversion from level 1 to level 2
@@ -177,6 +392,283 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_envelope_no_duplicates() {
+        // Float stepping over many cells used to land two sample points in the same mesh
+        for level in [
+            MeshLevel::Lv3,
+            MeshLevel::Lv4,
+            MeshLevel::Lv5,
+            MeshLevel::X2_5,
+        ] {
+            let sw = MeshCode::try_from_latlng(35.0, 139.0, level).unwrap();
+            let ne = sw.step(37, 53).unwrap();
+            let result = to_envelope(&sw, &ne).unwrap();
+            assert_eq!(result.len(), 38 * 54, "Failed for {}", level);
+            let unique: alloc::collections::BTreeSet<_> = result.iter().collect();
+            assert_eq!(unique.len(), result.len(), "Failed for {}", level);
+            // Ordered row by row from the south-west
+            assert_eq!(result.first(), Some(&sw));
+            assert_eq!(result.last(), Some(&ne));
+        }
+
+        for (from, to_level) in [
+            (5339, MeshLevel::Lv3),
+            (5339, MeshLevel::Lv4),
+            (533935, MeshLevel::Lv6),
+        ] {
+            let meshcode = MeshCode::try_from(from).unwrap();
+            let result = to_intersects(&meshcode, to_level).unwrap();
+            let unique: alloc::collections::BTreeSet<_> = result.iter().collect();
+            assert_eq!(
+                unique.len(),
+                result.len(),
+                "Failed for {} to {}",
+                from,
+                to_level
+            );
+            assert!(result.iter().all(|code| meshcode.contains(code)));
+        }
+    }
+
+    #[test]
+    fn test_to_intersects_extended_level() {
+        // X16 meshes don't align with Lv2 meshes: 5339227 spans 16-32 km from the Lv1
+        // south-west corner, which touches three rows and three columns of Lv2 meshes
+        let meshcode = MeshCode::try_from(5339227).unwrap();
+        let result = to_intersects(&meshcode, MeshLevel::Lv2).unwrap();
+        let values: Vec<u64> = result.iter().map(|&c| c.into()).collect();
+        assert_eq!(
+            values,
+            vec![
+                533911, 533912, 533913, 533921, 533922, 533923, 533931, 533932, 533933
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_intersects_coarser_level() {
+        let meshcode: MeshCode = 53393599.try_into().unwrap(); // Level 3
+
+        let result = to_intersects(&meshcode, MeshLevel::Lv1).unwrap();
+        assert_eq!(result, vec![meshcode.lower_level(MeshLevel::Lv1).unwrap()]);
+
+        let result = to_intersects(&meshcode, MeshLevel::Lv2).unwrap();
+        assert_eq!(result, vec![meshcode.lower_level(MeshLevel::Lv2).unwrap()]);
+
+        let result = to_intersects(&meshcode, MeshLevel::X40).unwrap();
+        assert_eq!(result, vec![meshcode.lower_level(MeshLevel::X40).unwrap()]);
+
+        // A coarser level whose boundaries don't line up may return more than one mesh code
+        let meshcode: MeshCode = 53393592.try_into().unwrap();
+        let result = to_intersects(&meshcode, MeshLevel::X2_5).unwrap();
+        assert_eq!(result.len(), 2);
+        assert!(result.iter().all(|code| code.intersects(&meshcode)));
+    }
+
+    #[test]
+    fn test_to_intersects_with_margin() {
+        let meshcode = MeshCode::try_from(533935).unwrap();
+
+        // No margin is the same as to_intersects
+        let default = to_intersects(&meshcode, MeshLevel::Lv3).unwrap();
+        let exact = to_intersects_with_margin(&meshcode, MeshLevel::Lv3, 0.0).unwrap();
+        assert_eq!(exact, default);
+
+        // A tiny margin adds the cells touching the edge
+        let touching = to_intersects_with_margin(&meshcode, MeshLevel::Lv3, 0.01).unwrap();
+        assert_eq!(touching.len(), 12 * 12);
+
+        // A larger margin yields a superset of the default result
+        let wide = to_intersects_with_margin(&meshcode, MeshLevel::Lv3, 1.5).unwrap();
+        assert_eq!(wide.len(), 14 * 14);
+        for code in &default {
+            assert!(wide.contains(code));
+        }
+        for code in &touching {
+            assert!(wide.contains(code));
+        }
+    }
+
+    #[test]
+    fn test_to_intersects_with_margin_coarser_level() {
+        let meshcode = MeshCode::try_from(53393599).unwrap();
+        let exact = to_intersects_with_margin(&meshcode, MeshLevel::Lv2, 0.0).unwrap();
+        assert_eq!(exact, to_intersects(&meshcode, MeshLevel::Lv2).unwrap());
+
+        // The north-east corner of 533935 touches three more Lv2 mesh codes
+        let touching = to_intersects_with_margin(&meshcode, MeshLevel::Lv2, 0.01).unwrap();
+        let touching: Vec<u64> = touching.into_iter().map(|c| c.into()).collect();
+        assert_eq!(touching, vec![533935, 533936, 533945, 533946]);
+    }
+
+    #[test]
+    fn test_to_intersects_with_margin_invalid() {
+        let meshcode = MeshCode::try_from(533935).unwrap();
+        assert_eq!(
+            to_intersects_with_margin(&meshcode, MeshLevel::Lv3, -0.5),
+            Err(JismeshError::InvalidMargin(-0.5))
+        );
+        assert_eq!(
+            to_intersects_with_margin(&meshcode, MeshLevel::Lv3, f64::INFINITY),
+            Err(JismeshError::InvalidMargin(f64::INFINITY))
+        );
+        assert!(matches!(
+            to_intersects_with_margin(&meshcode, MeshLevel::Lv3, f64::NAN),
+            Err(JismeshError::InvalidMargin(m)) if m.is_nan()
+        ));
+    }
+
+    #[test]
+    fn test_bounding_envelope() {
+        let codes: Vec<MeshCode> = [53393599, 53394500, 53393690, 53392589]
+            .iter()
+            .map(|&v| MeshCode::try_from(v).unwrap())
+            .collect();
+        let (sw, ne) = bounding_envelope(&codes).unwrap();
+        assert_eq!(sw.value, 53392580);
+        assert_eq!(ne.value, 53394600);
+
+        // The envelope of the corners contains every input code
+        let envelope = to_envelope(&sw, &ne).unwrap();
+        for code in &codes {
+            assert!(envelope.contains(code));
+        }
+
+        let single = [MeshCode::try_from(53393599).unwrap()];
+        assert_eq!(bounding_envelope(&single).unwrap(), (single[0], single[0]));
+    }
+
+    #[test]
+    fn test_bounding_envelope_errors() {
+        assert_eq!(bounding_envelope(&[]), Err(JismeshError::EmptyInput));
+        let codes = [
+            MeshCode::try_from(53393599).unwrap(),
+            MeshCode::try_from(533935).unwrap(),
+        ];
+        assert_eq!(
+            bounding_envelope(&codes),
+            Err(JismeshError::MismatchedMeshLevels(
+                MeshLevel::Lv3,
+                MeshLevel::Lv2
+            ))
+        );
+    }
+
+    #[test]
+    fn test_align_bbox() {
+        // A bbox slightly inside one mesh snaps to that mesh
+        let meshcode = MeshCode::try_from(53393599).unwrap();
+        let (s, w, n, e) = meshcode.bounds().unwrap();
+        let d = 1e-4;
+        let (sw, ne) = align_bbox(s + d, w + d, n - d, e - d, MeshLevel::Lv3).unwrap();
+        assert_eq!((sw, ne), (meshcode, meshcode));
+
+        // The exact bounds of a mesh also snap to that mesh
+        let (sw, ne) = align_bbox(s, w, n, e, MeshLevel::Lv3).unwrap();
+        assert_eq!((sw, ne), (meshcode, meshcode));
+
+        // Crossing the edge by a little adds a row and a column
+        let (sw, ne) = align_bbox(s - d, w + d, n - d, e + d, MeshLevel::Lv3).unwrap();
+        assert_eq!(sw, meshcode.step(-1, 0).unwrap());
+        assert_eq!(ne, meshcode.step(0, 1).unwrap());
+
+        // The envelope covers the original bbox
+        let (lat_s, lon_w, lat_n, lon_e) = (35.61, 139.62, 35.72, 139.81);
+        let (sw, ne) = align_bbox(lat_s, lon_w, lat_n, lon_e, MeshLevel::Lv3).unwrap();
+        let (sw_s, sw_w, _, _) = sw.bounds().unwrap();
+        let (_, _, ne_n, ne_e) = ne.bounds().unwrap();
+        assert!(sw_s <= lat_s && sw_w <= lon_w && lat_n <= ne_n && lon_e <= ne_e);
+
+        assert_eq!(
+            align_bbox(35.0, 139.0, 70.0, 140.0, MeshLevel::Lv3),
+            Err(JismeshError::LatitudeOutOfBounds {
+                value: 70.0,
+                index: 0,
+            })
+        );
+        assert_eq!(
+            align_bbox(36.0, 139.0, 35.0, 140.0, MeshLevel::Lv3),
+            Err(JismeshError::InvalidBoundingBox(36.0, 139.0, 35.0, 140.0))
+        );
+        assert_eq!(
+            align_bbox(35.0, 140.0, 36.0, 139.0, MeshLevel::Lv3),
+            Err(JismeshError::InvalidBoundingBox(35.0, 140.0, 36.0, 139.0))
+        );
+    }
+
+    #[test]
+    fn test_envelope_sample() {
+        // 533900 .. 533977 is the whole Lv1 mesh 5339 at level 2 (64 meshes)
+        let meshcode_sw = MeshCode::try_from(533900).unwrap();
+        let meshcode_ne = MeshCode::try_from(533977).unwrap();
+        let envelope = to_envelope(&meshcode_sw, &meshcode_ne).unwrap();
+
+        let sample = envelope_sample(&meshcode_sw, &meshcode_ne, 10, 42).unwrap();
+        assert_eq!(sample.len(), 10);
+        for code in sample.iter() {
+            assert!(envelope.contains(code));
+        }
+
+        // Reproducible with the same seed
+        let again = envelope_sample(&meshcode_sw, &meshcode_ne, 10, 42).unwrap();
+        assert_eq!(sample, again);
+        let other = envelope_sample(&meshcode_sw, &meshcode_ne, 10, 7).unwrap();
+        assert_ne!(sample, other);
+
+        // The sample size is capped by the envelope size
+        let all = envelope_sample(&meshcode_sw, &meshcode_ne, 100, 42).unwrap();
+        assert_eq!(all.len(), 64);
+
+        // Swapped corners are rejected like in to_envelope
+        assert_eq!(
+            envelope_sample(&meshcode_ne, &meshcode_sw, 10, 42),
+            Err(JismeshError::InvalidEnvelopeCorners(533977, 533900))
+        );
+    }
+
+    #[test]
+    fn test_expand_envelope() {
+        let meshcode_sw = MeshCode::try_from(58405438).unwrap();
+        let meshcode_ne = MeshCode::try_from(58405449).unwrap();
+        let current = to_envelope(&meshcode_sw, &meshcode_ne).unwrap();
+
+        // East adds exactly one column of 2 rows, crossing into the next Lv2 mesh
+        let added = expand_envelope(&current, Direction::E, MeshLevel::Lv3).unwrap();
+        assert_eq!(added.len(), 2);
+        assert_eq!(added[0], 58405530);
+        assert_eq!(added[1], 58405540);
+
+        let added = expand_envelope(&current, Direction::S, MeshLevel::Lv3).unwrap();
+        assert_eq!(added.len(), 2);
+        assert_eq!(added[0], 58405428);
+        assert_eq!(added[1], 58405429);
+
+        // Diagonal adds a row, a column and the corner
+        let added = expand_envelope(&current, Direction::NE, MeshLevel::Lv3).unwrap();
+        assert_eq!(added.len(), 5);
+        for code in added.iter() {
+            assert!(!current.contains(code));
+        }
+    }
+
+    #[test]
+    fn test_expand_envelope_errors() {
+        assert_eq!(
+            expand_envelope(&[], Direction::E, MeshLevel::Lv3),
+            Err(JismeshError::EmptyInput)
+        );
+
+        let current = vec![MeshCode::try_from(58405438).unwrap()];
+        assert_eq!(
+            expand_envelope(&current, Direction::E, MeshLevel::Lv2),
+            Err(JismeshError::MismatchedMeshLevels(
+                MeshLevel::Lv2,
+                MeshLevel::Lv3
+            ))
+        );
+    }
+
     #[test]
     fn test_error_mismatched_levels() {
         // Test with mismatched mesh levels
@@ -186,4 +678,111 @@ mod tests {
         let result = to_envelope(&meshcode_sw, &meshcode_ne);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_error_swapped_corners() {
+        let meshcode_sw: MeshCode = 58405438.try_into().unwrap();
+        let meshcode_ne: MeshCode = 58405449.try_into().unwrap();
+        assert_eq!(
+            to_envelope(&meshcode_ne, &meshcode_sw),
+            Err(JismeshError::InvalidEnvelopeCorners(58405449, 58405438))
+        );
+
+        // North-west and south-east corners are rejected too
+        let north_west = meshcode_sw.step(1, -1).unwrap();
+        assert_eq!(
+            to_envelope(&meshcode_sw, &north_west),
+            Err(JismeshError::InvalidEnvelopeCorners(
+                58405438,
+                north_west.into()
+            ))
+        );
+        let south_east = meshcode_sw.step(-1, 1).unwrap();
+        assert!(to_envelope(&meshcode_sw, &south_east).is_err());
+
+        // A degenerate envelope of a single mesh is valid
+        assert_eq!(
+            to_envelope(&meshcode_sw, &meshcode_sw).unwrap(),
+            vec![meshcode_sw]
+        );
+    }
+
+    #[test]
+    fn test_iter_in_bbox() {
+        // Same meshes and order as to_envelope for the bounds of the corner meshes
+        let meshcode_sw: MeshCode = 53394519.try_into().unwrap();
+        let meshcode_ne: MeshCode = 53394641.try_into().unwrap();
+        let (lat_s, lon_w, _, _) = meshcode_sw.bounds().unwrap();
+        let (_, _, lat_n, lon_e) = meshcode_ne.bounds().unwrap();
+        let result: Vec<MeshCode> = iter_in_bbox(lat_s, lon_w, lat_n, lon_e, MeshLevel::Lv3)
+            .unwrap()
+            .collect();
+        assert_eq!(result, to_envelope(&meshcode_sw, &meshcode_ne).unwrap());
+
+        // A viewport that doesn't line up with the grid
+        let result: Vec<u64> = iter_in_bbox(35.5, 139.5, 36.5, 140.5, MeshLevel::Lv1)
+            .unwrap()
+            .map(u64::from)
+            .collect();
+        assert_eq!(result, vec![5339, 5340, 5439, 5440]);
+
+        // A degenerate bounding box inside a single mesh
+        let result: Vec<u64> = iter_in_bbox(35.5, 139.5, 35.5, 139.5, MeshLevel::Lv1)
+            .unwrap()
+            .map(u64::from)
+            .collect();
+        assert_eq!(result, vec![5339]);
+
+        // Edges at the end of the valid range are allowed
+        assert!(iter_in_bbox(66.0, 179.0, 66.66, 180.0, MeshLevel::Lv1).is_ok());
+    }
+
+    #[test]
+    fn test_iter_in_bbox_errors() {
+        assert_eq!(
+            iter_in_bbox(36.0, 139.0, 35.0, 140.0, MeshLevel::Lv1).err(),
+            Some(JismeshError::InvalidBoundingBox(36.0, 139.0, 35.0, 140.0))
+        );
+        assert_eq!(
+            iter_in_bbox(35.0, 140.0, 36.0, 139.0, MeshLevel::Lv1).err(),
+            Some(JismeshError::InvalidBoundingBox(35.0, 140.0, 36.0, 139.0))
+        );
+        assert_eq!(
+            iter_in_bbox(-1.0, 139.0, 36.0, 140.0, MeshLevel::Lv1).err(),
+            Some(JismeshError::LatitudeOutOfBounds {
+                value: -1.0,
+                index: 0,
+            })
+        );
+        assert_eq!(
+            iter_in_bbox(35.0, 139.0, 36.0, 180.5, MeshLevel::Lv1).err(),
+            Some(JismeshError::LongitudeOutOfBounds {
+                value: 180.5,
+                index: 1,
+            })
+        );
+        assert_eq!(
+            iter_in_bbox(35.0, 99.5, 36.0, 140.0, MeshLevel::Lv1).err(),
+            Some(JismeshError::LongitudeOutOfBounds {
+                value: 99.5,
+                index: 0,
+            })
+        );
+        assert_eq!(
+            iter_in_bbox(35.0, 139.0, 67.0, 140.0, MeshLevel::Lv1).err(),
+            Some(JismeshError::LatitudeOutOfBounds {
+                value: 67.0,
+                index: 1,
+            })
+        );
+        // Only the north-east corner may lie on the far edge of the domain
+        assert_eq!(
+            iter_in_bbox(66.66, 179.0, 66.66, 180.0, MeshLevel::Lv1).err(),
+            Some(JismeshError::LatitudeOutOfBounds {
+                value: 66.66,
+                index: 0,
+            })
+        );
+        assert!(iter_in_bbox(f64::NAN, 139.0, 36.0, 140.0, MeshLevel::Lv1).is_err());
+    }
 }
@@ -1,18 +1,29 @@
 use super::MeshLevel;
+#[cfg(not(feature = "std"))]
+use super::String;
 use thiserror::Error;
 
 #[derive(Error, Debug, PartialEq)]
 pub enum JismeshError {
-    #[error("Latitude {0} is out of bounds (0 <= lat < 66.66)")]
-    LatitudeOutOfBounds(f64),
-    #[error("Longitude {0} is out of bounds (100 <= lon < 180)")]
-    LongitudeOutOfBounds(f64),
+    /// `index` is the position of the value in the input slice (0 for a single coordinate).
+    #[error(
+        "Latitude {value} at index {index} is out of bounds of the Japanese mesh system (0 <= lat < 66.66)"
+    )]
+    LatitudeOutOfBounds { value: f64, index: usize },
+    /// `index` is the position of the value in the input slice (0 for a single coordinate).
+    #[error(
+        "Longitude {value} at index {index} is out of bounds of the Japanese mesh system (100 <= lon < 180)"
+    )]
+    LongitudeOutOfBounds { value: f64, index: usize },
 
     #[error("Invalid meshcode: cannot determine level for {0}")]
     UnknownMeshLevelForCode(u64),
     #[error("Invalid meshcode at level {0}: {1}")]
     InvalidMeshcodeAtLevel(usize, u64),
 
+    #[error("Meshcode {0} is at level {1}, not {2}")]
+    UnexpectedMeshLevel(u64, MeshLevel, MeshLevel),
+
     #[error("Invalid mesh level: {0}")]
     InvalidMeshLevel(usize),
 
@@ -22,6 +33,18 @@ pub enum JismeshError {
     #[error("{0} is not lower than {1}")]
     InvalidMeshLevelForLowerLevel(MeshLevel, MeshLevel),
 
+    #[error("{0} is not higher than {1}")]
+    InvalidMeshLevelForHigherLevel(MeshLevel, MeshLevel),
+
+    #[error("Multipliers ({0}, {1}) are out of range (0 <= multiplier <= 1)")]
+    MultiplierOutOfRange(f64, f64),
+
+    #[error("Jitter {value} is out of range ({min} <= jitter <= {max})")]
+    JitterOutOfRange { value: f64, min: f64, max: f64 },
+
+    #[error("Point ({0}, {1}) is outside of meshcode {2}")]
+    PointOutsideMeshCode(f64, f64, u64),
+
     #[error("Unsupported mesh level conversion from {0} to {1}")]
     UnsupportedMeshLevelConversion(MeshLevel, MeshLevel),
 
@@ -30,8 +53,76 @@ pub enum JismeshError {
     )]
     MismatchedMeshLevels(MeshLevel, MeshLevel),
 
-    #[error("Parse Error: {0}")]
-    ParseError(#[from] strum::ParseError),
+    #[error("Meshcode {1} is not north-east of meshcode {0}")]
+    InvalidEnvelopeCorners(u64, u64),
+
+    #[error("Invalid bounding box: ({0}, {1}) is not south-west of ({2}, {3})")]
+    InvalidBoundingBox(f64, f64, f64, f64),
+
+    #[error("Meshcode {0} does not form a valid polygon within 100 <= lon <= 180")]
+    InvalidMeshPolygon(u64),
+
+    #[error("Segments per edge must be between 1 and 10000, got {0}")]
+    InvalidSegmentCount(usize),
+
+    #[error("Stepping ({1}, {2}) from meshcode {0} leaves the valid range")]
+    StepOutOfBounds(u64, i64, i64),
+
+    #[error("Input is empty")]
+    EmptyInput,
+
+    #[error("Global index ({1}, {2}) is out of bounds for level {0}")]
+    GlobalIndexOutOfBounds(MeshLevel, u64, u64),
+
+    #[error("Invalid polygon: at least 3 points are required, got {0}")]
+    InvalidPolygon(usize),
+
+    #[error("Degenerate polygon: the {0} points enclose no area")]
+    DegeneratePolygon(usize),
+
+    #[error("Invalid line: at least 2 points are required, got {0}")]
+    InvalidLine(usize),
+
+    #[error("Radius {0} must not be negative")]
+    NegativeRadius(f64),
+
+    #[error("Radius {0} must be a finite number")]
+    NonFiniteRadius(f64),
+
+    #[error("Margin {0} must be a finite, non-negative number")]
+    InvalidMargin(f64),
+
+    #[error("Invalid spatial key: {0:#x}")]
+    InvalidSpatialKey(u64),
+
+    #[error("Invalid coverage data: {0}")]
+    InvalidCoverageData(String),
+
+    #[error("Grid of {height} x {width} mesh codes exceeds the limit of {max} mesh codes")]
+    GridTooLarge {
+        height: usize,
+        width: usize,
+        max: usize,
+    },
+
+    #[error("Unsupported EPSG code: {0}")]
+    UnsupportedEpsgCode(u32),
+
+    #[error("GeoPackage error: {0}")]
+    GeoPackageError(String),
+
+    #[cfg_attr(feature = "std", error("Parse Error: {0}"))]
+    #[cfg_attr(not(feature = "std"), error("Parse Error: {0:?}"))]
+    ParseError(#[cfg_attr(feature = "std", from)] strum::ParseError),
+}
+
+// `strum::ParseError` only implements `Display` and `Error` with `std`, so it can't be a
+// `#[from]` source here.
+#[cfg(not(feature = "std"))]
+impl From<strum::ParseError> for JismeshError {
+    fn from(err: strum::ParseError) -> Self {
+        JismeshError::ParseError(err)
+    }
 }
 
-pub type Result<T> = std::result::Result<T, JismeshError>;
+pub type Result<T> = core::result::Result<T, JismeshError>;
@@ -0,0 +1,367 @@
+use super::*;
+
+/// WKB geometry type code for a 2D polygon
+const WKB_POLYGON: u32 = 3;
+
+/// Encodes a single-ring polygon as little-endian OGC well-known binary.
+pub(crate) fn ring_to_wkb(ring: &[(f64, f64)]) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(13 + ring.len() * 16);
+    buf.push(1); // little endian
+    buf.extend_from_slice(&WKB_POLYGON.to_le_bytes());
+    buf.extend_from_slice(&1u32.to_le_bytes()); // number of rings
+    buf.extend_from_slice(&(ring.len() as u32).to_le_bytes());
+    for &(x, y) in ring {
+        buf.extend_from_slice(&x.to_le_bytes());
+        buf.extend_from_slice(&y.to_le_bytes());
+    }
+    buf
+}
+
+/// Joins formatted `x y` positions into a single-ring WKT polygon.
+fn ring_to_wkt(positions: impl Iterator<Item = String>) -> String {
+    format!("POLYGON(({}))", positions.collect::<Vec<_>>().join(", "))
+}
+
+impl MeshCode {
+    /// メッシュの外周を閉じたリング（経度, 緯度）として取得する。
+    ///
+    /// 南西 → 南東 → 北東 → 北西 → 南西 の反時計回り 5 点で、GeoJSON / WKT などの
+    /// 出力の基になります。座標の順番は (経度, 緯度) です。
+    ///
+    /// 座標を計算できない（東端が経度 180 度を超えるなど）、または幅・高さが 0 になるメッシュは
+    /// エラーを返します。
+    pub fn to_ring(&self) -> Result<Vec<(f64, f64)>> {
+        let (lat_s, lon_w) = self.point(0.0, 0.0)?;
+        let (lat_n, lon_e) = self.point(1.0, 1.0)?;
+
+        // `point` already rejects corners beyond 180 degrees
+        debug_assert!(lon_e <= 180.0);
+        if lon_w >= lon_e || lat_s >= lat_n {
+            return Err(JismeshError::InvalidMeshPolygon(self.value));
+        }
+
+        Ok(vec![
+            (lon_w, lat_s),
+            (lon_e, lat_s),
+            (lon_e, lat_n),
+            (lon_w, lat_n),
+            (lon_w, lat_s),
+        ])
+    }
+
+    /// `to_ring` と同じですが、各辺を segments_per_edge 個に等分して頂点を追加したリングを取得する。
+    ///
+    /// 緯度経度では長方形のままですが、頂点が増えるため、曲がって見える投影法に
+    /// 再投影する際に辺を滑らかに描画できます。結果は 4 * segments_per_edge + 1 点で、
+    /// segments_per_edge が 1 の場合は `to_ring` と同じです。
+    ///
+    /// segments_per_edge が 0 、または 10,000 を超える場合はエラーを返します。
+    pub fn to_polygon_densified(&self, segments_per_edge: usize) -> Result<Vec<(f64, f64)>> {
+        const MAX_SEGMENTS_PER_EDGE: usize = 10_000;
+        let n = segments_per_edge;
+        if !(1..=MAX_SEGMENTS_PER_EDGE).contains(&n) {
+            return Err(JismeshError::InvalidSegmentCount(n));
+        }
+        let corners = self.to_ring()?;
+
+        let mut ring = Vec::with_capacity(4 * n + 1);
+        for edge in corners.windows(2) {
+            let ((x0, y0), (x1, y1)) = (edge[0], edge[1]);
+            for i in 0..n {
+                let t = i as f64 / n as f64;
+                ring.push((x0 + (x1 - x0) * t, y0 + (y1 - y0) * t));
+            }
+        }
+        ring.push(corners[0]);
+        Ok(ring)
+    }
+
+    /// メッシュのポリゴンを OGC WKB (Well-Known Binary) として取得する。
+    /// バイト順はリトルエンディアンで、座標の順番は (経度, 緯度) です。
+    /// PostGIS や GeoPackage への取り込みに使えます。
+    pub fn to_wkb(&self) -> Result<Vec<u8>> {
+        Ok(ring_to_wkb(&self.to_ring()?))
+    }
+
+    /// メッシュのポリゴンを OGC WKT (Well-Known Text) として取得する。
+    /// 形式は `POLYGON((経度 緯度, 経度 緯度, ...))` です。
+    ///
+    /// 外周は `to_ring` と同じく始点で閉じます。
+    /// 座標は元の値に戻せる最短の桁数で出力します。桁数を指定する場合は `to_wkt_with_precision` を使ってください。
+    pub fn to_wkt(&self) -> Result<String> {
+        let ring = self.to_ring()?;
+        Ok(ring_to_wkt(
+            ring.iter().map(|(x, y)| format!("{} {}", x, y)),
+        ))
+    }
+
+    /// `to_wkt` と同じですが、座標を小数点以下 decimals 桁で出力します。
+    ///
+    /// f64 は 17 桁を超える精度を持たないため、decimals は 17 までに切り詰めます。
+    pub fn to_wkt_with_precision(&self, decimals: usize) -> Result<String> {
+        const MAX_DECIMALS: usize = 17;
+        let decimals = decimals.min(MAX_DECIMALS);
+        let ring = self.to_ring()?;
+        Ok(ring_to_wkt(ring.iter().map(|(x, y)| {
+            format!("{:.*} {:.*}", decimals, x, decimals, y)
+        })))
+    }
+
+    /// CSV の 1 行として `メッシュコード,中心の緯度,中心の経度` を取得する。
+    /// 緯度経度は小数点以下 6 桁で出力します。桁数を変える場合は `to_row_with_precision` を使ってください。
+    pub fn to_row(&self) -> Result<String> {
+        self.to_row_with_precision(6)
+    }
+
+    /// `to_row` と同じですが、緯度経度を小数点以下 precision 桁で出力します。
+    pub fn to_row_with_precision(&self, precision: usize) -> Result<String> {
+        let (lat, lon) = self.point(0.5, 0.5)?;
+        Ok(format!(
+            "{},{:.*},{:.*}",
+            self.value, precision, lat, precision, lon
+        ))
+    }
+}
+
+/// Exports mesh codes as a GeoJSON `FeatureCollection`.
+///
+/// Each feature is the polygon of a mesh (see `MeshCode::to_ring`) with the properties
+/// `code` (the mesh code as a number) and `level` (the `MeshLevel` display string).
+/// Coordinates are in (longitude, latitude) order, as required by the GeoJSON spec.
+///
+/// # Arguments
+/// * `codes` - The mesh codes to export. Levels may be mixed.
+///
+/// # Returns
+/// * `Result<String>` - The GeoJSON text
+///
+/// # Errors
+/// * Returns an error if a mesh code doesn't form a valid polygon
+#[cfg(feature = "geojson")]
+pub fn to_geojson(codes: &[MeshCode]) -> Result<String> {
+    let features = codes
+        .iter()
+        .map(|code| {
+            let coordinates = code
+                .to_ring()?
+                .iter()
+                .map(|(lon, lat)| format!("[{},{}]", lon, lat))
+                .collect::<Vec<_>>()
+                .join(",");
+            Ok(format!(
+                r#"{{"type":"Feature","geometry":{{"type":"Polygon","coordinates":[[{}]]}},"properties":{{"code":{},"level":"{}"}}}}"#,
+                coordinates, code.value, code.level
+            ))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(format!(
+        r#"{{"type":"FeatureCollection","features":[{}]}}"#,
+        features.join(",")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_ring() {
+        let meshcode = MeshCode::try_from(53393599).unwrap();
+        let ring = meshcode.to_ring().unwrap();
+        assert_eq!(
+            ring,
+            vec![
+                (139.7375, 35.65833333333333),
+                (139.75, 35.65833333333333),
+                (139.75, 35.666666666666664),
+                (139.7375, 35.666666666666664),
+                (139.7375, 35.65833333333333),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_polygon_densified() {
+        let meshcode = MeshCode::try_from(53393599).unwrap();
+        let ring = meshcode.to_ring().unwrap();
+        assert_eq!(meshcode.to_polygon_densified(1).unwrap(), ring);
+
+        let dense = meshcode.to_polygon_densified(4).unwrap();
+        assert_eq!(dense.len(), 4 * 4 + 1);
+        assert_eq!(dense.first(), dense.last());
+        // The corners are kept, and the midpoints lie on the edges
+        for (i, corner) in ring.iter().enumerate() {
+            assert_eq!(&dense[i * 4], corner);
+        }
+        assert_eq!(dense[2].1, ring[0].1);
+        assert!((dense[2].0 - (ring[0].0 + ring[1].0) / 2.0).abs() < 1e-12);
+
+        assert_eq!(
+            meshcode.to_polygon_densified(0),
+            Err(JismeshError::InvalidSegmentCount(0))
+        );
+        assert_eq!(
+            meshcode.to_polygon_densified(10_000).unwrap().len(),
+            4 * 10_000 + 1
+        );
+        for n in [10_001, usize::MAX / 4 + 1, usize::MAX] {
+            assert_eq!(
+                meshcode.to_polygon_densified(n),
+                Err(JismeshError::InvalidSegmentCount(n))
+            );
+        }
+    }
+
+    #[test]
+    fn test_ring_to_wkb() {
+        let ring = vec![(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0), (0.0, 0.0)];
+        let wkb = ring_to_wkb(&ring);
+        assert_eq!(wkb.len(), 1 + 4 + 4 + 4 + 5 * 16);
+        assert_eq!(&wkb[..13], &[1, 3, 0, 0, 0, 1, 0, 0, 0, 5, 0, 0, 0]);
+        assert_eq!(&wkb[13 + 16..13 + 24], &1.0f64.to_le_bytes());
+    }
+
+    #[test]
+    fn test_to_wkb() {
+        use geo_traits::{CoordTrait, GeometryTrait, GeometryType, LineStringTrait, PolygonTrait};
+
+        let meshcode = MeshCode::try_from(53393599).unwrap();
+        let bytes = meshcode.to_wkb().unwrap();
+        let geometry = wkb::reader::read_wkb(&bytes).unwrap();
+        let GeometryType::Polygon(polygon) = geometry.as_type() else {
+            panic!("Expected a polygon");
+        };
+        assert_eq!(polygon.num_interiors(), 0);
+        let coords: Vec<(f64, f64)> = polygon
+            .exterior()
+            .unwrap()
+            .coords()
+            .map(|c| (c.x(), c.y()))
+            .collect();
+        assert_eq!(coords, meshcode.to_ring().unwrap());
+
+        let (lat_s, lon_w) = meshcode.point(0.0, 0.0).unwrap();
+        let (lat_n, lon_e) = meshcode.point(1.0, 1.0).unwrap();
+        assert_eq!(coords[0], (lon_w, lat_s));
+        assert_eq!(coords[2], (lon_e, lat_n));
+    }
+
+    #[test]
+    fn test_to_wkt() {
+        let meshcode = MeshCode::try_from(53393599).unwrap();
+        assert_eq!(
+            meshcode.to_wkt_with_precision(6).unwrap(),
+            "POLYGON((139.737500 35.658333, 139.750000 35.658333, 139.750000 35.666667, \
+             139.737500 35.666667, 139.737500 35.658333))"
+        );
+        assert_eq!(
+            meshcode.to_wkt_with_precision(2).unwrap(),
+            "POLYGON((139.74 35.66, 139.75 35.66, 139.75 35.67, 139.74 35.67, 139.74 35.66))"
+        );
+        // Decimals beyond 17 are clamped instead of overflowing the formatter
+        assert_eq!(
+            meshcode.to_wkt_with_precision(usize::MAX).unwrap(),
+            meshcode.to_wkt_with_precision(17).unwrap()
+        );
+
+        // Full precision parses back to the ring exactly
+        let wkt = meshcode.to_wkt().unwrap();
+        let inner = wkt
+            .strip_prefix("POLYGON((")
+            .and_then(|s| s.strip_suffix("))"))
+            .unwrap();
+        let parsed: Vec<(f64, f64)> = inner
+            .split(", ")
+            .map(|position| {
+                let (x, y) = position.split_once(' ').unwrap();
+                (x.parse().unwrap(), y.parse().unwrap())
+            })
+            .collect();
+        assert_eq!(parsed, meshcode.to_ring().unwrap());
+        assert_eq!(parsed.first(), parsed.last());
+    }
+
+    #[test]
+    fn test_to_row() {
+        let meshcode = MeshCode::try_from(53394611).unwrap();
+        assert_eq!(meshcode.to_row().unwrap(), "53394611,35.679167,139.768750");
+        assert_eq!(
+            meshcode.to_row_with_precision(3).unwrap(),
+            "53394611,35.679,139.769"
+        );
+        assert_eq!(
+            meshcode.to_row_with_precision(0).unwrap(),
+            "53394611,36,140"
+        );
+    }
+
+    #[test]
+    fn test_to_ring_domain_edge() {
+        // The easternmost column ends exactly at 180 degrees, which is still valid
+        let meshcode = MeshCode::try_from(537977).unwrap();
+        let ring = meshcode.to_ring().unwrap();
+        assert!(ring.iter().all(|&(lon, _)| lon <= 180.0));
+        assert_eq!(ring[1].0, 180.0);
+
+        // Anything beyond 180 degrees is rejected when calculating the corners
+        let meshcode = MeshCode {
+            value: 5380,
+            level: MeshLevel::Lv1,
+        };
+        assert_eq!(
+            meshcode.to_ring(),
+            Err(meshcode.point(1.0, 1.0).unwrap_err())
+        );
+    }
+
+    #[cfg(feature = "geojson")]
+    #[test]
+    fn test_to_geojson() {
+        use geojson::{GeoJson, GeometryValue};
+
+        let codes: Vec<MeshCode> = [53393599, 5339467]
+            .iter()
+            .map(|&v| MeshCode::try_from(v).unwrap())
+            .collect();
+        let text = to_geojson(&codes).unwrap();
+        let collection = match text.parse::<GeoJson>().unwrap() {
+            GeoJson::FeatureCollection(collection) => collection,
+            other => panic!("Expected a FeatureCollection, got {:?}", other),
+        };
+        assert_eq!(collection.features.len(), 2);
+
+        let feature = &collection.features[0];
+        assert_eq!(
+            feature.property("code").and_then(|v| v.as_u64()),
+            Some(53393599)
+        );
+        assert_eq!(
+            feature.property("level").and_then(|v| v.as_str()),
+            Some("Lv3")
+        );
+        assert_eq!(
+            collection.features[1]
+                .property("level")
+                .and_then(|v| v.as_str()),
+            Some("X16")
+        );
+
+        // Coordinates are (lon, lat) and match the ring of the mesh
+        let geometry = feature.geometry.as_ref().unwrap();
+        let GeometryValue::Polygon { coordinates: rings } = &geometry.value else {
+            panic!("Expected a Polygon, got {:?}", geometry.value);
+        };
+        assert_eq!(rings.len(), 1);
+        let ring = codes[0].to_ring().unwrap();
+        assert_eq!(rings[0].len(), ring.len());
+        for (position, (lon, lat)) in rings[0].iter().zip(ring) {
+            assert_eq!(position.len(), 2);
+            assert_eq!((position[0], position[1]), (lon, lat));
+        }
+
+        assert_eq!(
+            to_geojson(&[]).unwrap(),
+            r#"{"type":"FeatureCollection","features":[]}"#
+        );
+    }
+}
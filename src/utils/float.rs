@@ -0,0 +1,70 @@
+/// `f64` math for `no_std` builds, backed by `libm`.
+///
+/// The methods have the same names and signatures as the inherent `f64` methods of the standard
+/// library, so the rest of the crate compiles unchanged with or without `std`.
+// Some methods are only used by optional features such as `projection`.
+#[allow(dead_code)]
+pub(crate) trait FloatExt {
+    fn floor(self) -> Self;
+    fn ceil(self) -> Self;
+    fn sqrt(self) -> Self;
+    fn powi(self, n: i32) -> Self;
+    fn ln(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn asin(self) -> Self;
+    fn atanh(self) -> Self;
+    fn sinh(self) -> Self;
+    fn cosh(self) -> Self;
+    fn atan2(self, other: Self) -> Self;
+}
+
+impl FloatExt for f64 {
+    fn floor(self) -> Self {
+        libm::floor(self)
+    }
+
+    fn ceil(self) -> Self {
+        libm::ceil(self)
+    }
+
+    fn sqrt(self) -> Self {
+        libm::sqrt(self)
+    }
+
+    fn powi(self, n: i32) -> Self {
+        libm::pow(self, n as f64)
+    }
+
+    fn ln(self) -> Self {
+        libm::log(self)
+    }
+
+    fn sin(self) -> Self {
+        libm::sin(self)
+    }
+
+    fn cos(self) -> Self {
+        libm::cos(self)
+    }
+
+    fn asin(self) -> Self {
+        libm::asin(self)
+    }
+
+    fn atanh(self) -> Self {
+        libm::atanh(self)
+    }
+
+    fn sinh(self) -> Self {
+        libm::sinh(self)
+    }
+
+    fn cosh(self) -> Self {
+        libm::cosh(self)
+    }
+
+    fn atan2(self, other: Self) -> Self {
+        libm::atan2(self, other)
+    }
+}
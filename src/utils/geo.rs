@@ -0,0 +1,103 @@
+use super::*;
+use geo_types::{Coord, LineString, Point, Polygon, Rect};
+
+impl MeshCode {
+    /// `geo` の点からメッシュコードを生成する。
+    ///
+    /// `geo` の慣例に従い、 x を経度、 y を緯度として扱います。
+    pub fn from_geo_point(point: &Point<f64>, level: MeshLevel) -> Result<MeshCode> {
+        MeshCode::try_from_latlng(point.y(), point.x(), level)
+    }
+
+    /// メッシュを `geo` のポリゴンとして取得する。
+    ///
+    /// 外周は `to_ring` と同じ 南西 → 南東 → 北東 → 北西 → 南西 の反時計回りで、
+    /// x を経度、 y を緯度とします。
+    pub fn to_polygon(&self) -> Result<Polygon<f64>> {
+        let ring: Vec<Coord<f64>> = self
+            .to_ring()?
+            .into_iter()
+            .map(|(x, y)| Coord { x, y })
+            .collect();
+        Ok(Polygon::new(LineString::new(ring), vec![]))
+    }
+}
+
+/// メッシュの範囲を `geo` の長方形に変換する。 x を経度、 y を緯度とします。
+/// 範囲を求められないメッシュコード（例: 経度の桁が範囲外の 538000 ）の場合はエラーを返します。
+impl TryFrom<&MeshCode> for Rect<f64> {
+    type Error = JismeshError;
+
+    fn try_from(meshcode: &MeshCode) -> Result<Self> {
+        let (lat_s, lon_w, lat_n, lon_e) = meshcode.bounds()?;
+        Ok(Rect::new(
+            Coord { x: lon_w, y: lat_s },
+            Coord { x: lon_e, y: lat_n },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_geo_point() {
+        let point = Point::new(139.745433, 35.658581);
+        let meshcode = MeshCode::from_geo_point(&point, MeshLevel::Lv3).unwrap();
+        assert_eq!(meshcode, 53393599);
+
+        // Swapped coordinates are out of bounds
+        let swapped = Point::new(35.658581, 139.745433);
+        assert_eq!(
+            MeshCode::from_geo_point(&swapped, MeshLevel::Lv3),
+            Err(JismeshError::LatitudeOutOfBounds {
+                value: 139.745433,
+                index: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn test_rect_from_meshcode() {
+        let meshcode = MeshCode::try_from(53393599).unwrap();
+        let rect = Rect::try_from(&meshcode).unwrap();
+        let (lat_s, lon_w, lat_n, lon_e) = meshcode.bounds().unwrap();
+        assert_eq!(rect.min(), Coord { x: lon_w, y: lat_s });
+        assert_eq!(rect.max(), Coord { x: lon_e, y: lat_n });
+
+        // The longitude digits of 538000 are out of range, so it has no bounds
+        let meshcode = MeshCode::try_from(538000).unwrap();
+        assert_eq!(
+            Rect::try_from(&meshcode),
+            Err(meshcode.bounds().unwrap_err())
+        );
+    }
+
+    #[test]
+    fn test_to_polygon() {
+        let meshcode = MeshCode::try_from(53393599).unwrap();
+        let polygon = meshcode.to_polygon().unwrap();
+        assert!(polygon.interiors().is_empty());
+
+        let coords: Vec<Coord<f64>> = polygon.exterior().coords().copied().collect();
+        assert_eq!(coords.len(), 5);
+        assert_eq!(coords.first(), coords.last());
+
+        // The bounding rect of the ring matches the bounds of the mesh
+        let rect = Rect::try_from(&meshcode).unwrap();
+        let min_x = coords.iter().map(|c| c.x).fold(f64::INFINITY, f64::min);
+        let min_y = coords.iter().map(|c| c.y).fold(f64::INFINITY, f64::min);
+        let max_x = coords.iter().map(|c| c.x).fold(f64::NEG_INFINITY, f64::max);
+        let max_y = coords.iter().map(|c| c.y).fold(f64::NEG_INFINITY, f64::max);
+        assert_eq!(Coord { x: min_x, y: min_y }, rect.min());
+        assert_eq!(Coord { x: max_x, y: max_y }, rect.max());
+
+        // Counter-clockwise, as GeoJSON expects: the signed area is positive
+        let signed_area: f64 = coords
+            .windows(2)
+            .map(|w| w[0].x * w[1].y - w[1].x * w[0].y)
+            .sum();
+        assert!(signed_area > 0.0);
+    }
+}
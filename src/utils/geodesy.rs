@@ -0,0 +1,80 @@
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)] // see `utils/mod.rs`
+use super::FloatExt;
+
+/// Mean earth radius in meters (IUGG)
+pub(crate) const EARTH_RADIUS_M: f64 = 6_371_008.8;
+
+/// Great-circle distance in meters between two points on a sphere, using the haversine formula.
+pub(crate) fn haversine_distance(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let phi1 = lat1.to_radians();
+    let phi2 = lat2.to_radians();
+    let d_phi = (lat2 - lat1).to_radians();
+    let d_lambda = (lon2 - lon1).to_radians();
+
+    let a = (d_phi / 2.0).sin().powi(2) + phi1.cos() * phi2.cos() * (d_lambda / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_M * a.sqrt().asin()
+}
+
+/// Semi-major axis of the WGS84 ellipsoid in meters
+const WGS84_A: f64 = 6_378_137.0;
+/// Flattening of the WGS84 ellipsoid
+const WGS84_F: f64 = 1.0 / 298.257_223_563;
+
+/// Area in square meters of the region bounded by two parallels and two meridians on the WGS84
+/// ellipsoid.
+///
+/// Uses the closed-form integral of the ellipsoidal area element (the authalic latitude function
+/// `q`), so the result is exact up to floating point error, which is far below 1 m² for mesh cells.
+pub(crate) fn ellipsoidal_rect_area(lat_s: f64, lon_w: f64, lat_n: f64, lon_e: f64) -> f64 {
+    let e2 = WGS84_F * (2.0 - WGS84_F);
+    let e = e2.sqrt();
+    let b2 = WGS84_A * WGS84_A * (1.0 - e2);
+    let q = |lat: f64| {
+        let sin_phi = lat.to_radians().sin();
+        sin_phi / (1.0 - e2 * sin_phi * sin_phi) + (e * sin_phi).atanh() / e
+    };
+    b2 * (lon_e - lon_w).to_radians() / 2.0 * (q(lat_n) - q(lat_s))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_haversine_distance() {
+        assert_eq!(haversine_distance(35.0, 139.0, 35.0, 139.0), 0.0);
+
+        // One degree of latitude is about 111.2km on the mean sphere
+        assert_relative_eq!(
+            haversine_distance(35.0, 139.0, 36.0, 139.0),
+            111_195.0,
+            max_relative = 1e-4
+        );
+
+        // Tokyo station to Osaka station, about 403km
+        assert_relative_eq!(
+            haversine_distance(35.681236, 139.767125, 34.702485, 135.495951),
+            403_000.0,
+            max_relative = 1e-2
+        );
+    }
+
+    #[test]
+    fn test_ellipsoidal_rect_area() {
+        // The whole ellipsoid is about 510,065,622 km²
+        assert_relative_eq!(
+            ellipsoidal_rect_area(-90.0, -180.0, 90.0, 180.0),
+            510_065_621_724_000.0,
+            max_relative = 1e-9
+        );
+
+        // Lv1 mesh 5339, checked against a numerical integration of the area element
+        assert_relative_eq!(
+            ellipsoidal_rect_area(35.333333333333336, 139.0, 36.0, 140.0),
+            6_697_192_582.8,
+            max_relative = 1e-6
+        );
+    }
+}
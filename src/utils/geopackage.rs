@@ -0,0 +1,190 @@
+use super::export::ring_to_wkb;
+use super::*;
+use rusqlite::{Connection, params};
+use std::path::Path;
+
+/// SRS id of WGS 84 geographic coordinates in the GeoPackage
+const SRS_ID: i32 = 4326;
+
+impl From<rusqlite::Error> for JismeshError {
+    fn from(err: rusqlite::Error) -> Self {
+        JismeshError::GeoPackageError(err.to_string())
+    }
+}
+
+/// Encodes a ring as a GeoPackage geometry blob: the GPKG header with an XY envelope,
+/// followed by the WKB polygon.
+fn gpkg_geometry(ring: &[(f64, f64)]) -> Vec<u8> {
+    let (min_x, max_x, min_y, max_y) = ring.iter().fold(
+        (f64::MAX, f64::MIN, f64::MAX, f64::MIN),
+        |(min_x, max_x, min_y, max_y), &(x, y)| {
+            (min_x.min(x), max_x.max(x), min_y.min(y), max_y.max(y))
+        },
+    );
+
+    let mut buf = vec![b'G', b'P', 0];
+    // flags: little endian, envelope [minx, maxx, miny, maxy]
+    buf.push(0b0000_0011);
+    buf.extend_from_slice(&SRS_ID.to_le_bytes());
+    for v in [min_x, max_x, min_y, max_y] {
+        buf.extend_from_slice(&v.to_le_bytes());
+    }
+    buf.extend_from_slice(&ring_to_wkb(ring));
+    buf
+}
+
+/// Writes mesh codes to a new GeoPackage file, as a polygon feature table named `meshcodes`.
+///
+/// Each row has the cell polygon in WGS 84 (EPSG:4326) and the `meshcode` / `level`
+/// attributes, so the file can be opened directly in QGIS.
+///
+/// # Errors
+/// * Returns an error if the file already contains a `meshcodes` table, or can't be written
+/// * Returns an error if a mesh code can't be converted to a polygon
+pub fn write_geopackage<P: AsRef<Path>>(path: P, codes: &[MeshCode]) -> Result<()> {
+    let mut conn = Connection::open(path)?;
+    let tx = conn.transaction()?;
+
+    tx.execute_batch(
+        "PRAGMA application_id = 1196444487;
+        PRAGMA user_version = 10300;
+        CREATE TABLE IF NOT EXISTS gpkg_spatial_ref_sys (
+            srs_name TEXT NOT NULL,
+            srs_id INTEGER NOT NULL PRIMARY KEY,
+            organization TEXT NOT NULL,
+            organization_coordsys_id INTEGER NOT NULL,
+            definition TEXT NOT NULL,
+            description TEXT
+        );
+        CREATE TABLE IF NOT EXISTS gpkg_contents (
+            table_name TEXT NOT NULL PRIMARY KEY,
+            data_type TEXT NOT NULL,
+            identifier TEXT UNIQUE,
+            description TEXT DEFAULT '',
+            last_change DATETIME NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%fZ','now')),
+            min_x DOUBLE,
+            min_y DOUBLE,
+            max_x DOUBLE,
+            max_y DOUBLE,
+            srs_id INTEGER,
+            CONSTRAINT fk_gc_r_srs_id FOREIGN KEY (srs_id) REFERENCES gpkg_spatial_ref_sys(srs_id)
+        );
+        CREATE TABLE IF NOT EXISTS gpkg_geometry_columns (
+            table_name TEXT NOT NULL,
+            column_name TEXT NOT NULL,
+            geometry_type_name TEXT NOT NULL,
+            srs_id INTEGER NOT NULL,
+            z TINYINT NOT NULL,
+            m TINYINT NOT NULL,
+            CONSTRAINT pk_geom_cols PRIMARY KEY (table_name, column_name),
+            CONSTRAINT fk_gc_tn FOREIGN KEY (table_name) REFERENCES gpkg_contents(table_name),
+            CONSTRAINT fk_gc_srs FOREIGN KEY (srs_id) REFERENCES gpkg_spatial_ref_sys (srs_id)
+        );
+        INSERT OR IGNORE INTO gpkg_spatial_ref_sys VALUES
+            ('Undefined cartesian SRS', -1, 'NONE', -1, 'undefined', NULL),
+            ('Undefined geographic SRS', 0, 'NONE', 0, 'undefined', NULL),
+            ('WGS 84 geodetic', 4326, 'EPSG', 4326,
+             'GEOGCS[\"WGS 84\",DATUM[\"WGS_1984\",SPHEROID[\"WGS 84\",6378137,298.257223563,AUTHORITY[\"EPSG\",\"7030\"]],AUTHORITY[\"EPSG\",\"6326\"]],PRIMEM[\"Greenwich\",0,AUTHORITY[\"EPSG\",\"8901\"]],UNIT[\"degree\",0.0174532925199433,AUTHORITY[\"EPSG\",\"9122\"]],AUTHORITY[\"EPSG\",\"4326\"]]',
+             NULL);
+        CREATE TABLE meshcodes (
+            fid INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+            geom POLYGON,
+            meshcode INTEGER NOT NULL,
+            level TEXT NOT NULL
+        );
+        INSERT INTO gpkg_contents (table_name, data_type, identifier, srs_id)
+            VALUES ('meshcodes', 'features', 'meshcodes', 4326);
+        INSERT INTO gpkg_geometry_columns VALUES ('meshcodes', 'geom', 'POLYGON', 4326, 0, 0);",
+    )?;
+
+    let mut bbox = (f64::MAX, f64::MAX, f64::MIN, f64::MIN);
+    {
+        let mut stmt =
+            tx.prepare("INSERT INTO meshcodes (geom, meshcode, level) VALUES (?1, ?2, ?3)")?;
+        for code in codes {
+            let ring = code.to_ring()?;
+            let (lon_w, lat_s) = ring[0];
+            let (lon_e, lat_n) = ring[2];
+            bbox = (
+                bbox.0.min(lon_w),
+                bbox.1.min(lat_s),
+                bbox.2.max(lon_e),
+                bbox.3.max(lat_n),
+            );
+            stmt.execute(params![
+                gpkg_geometry(&ring),
+                code.value as i64,
+                code.level.to_string()
+            ])?;
+        }
+    }
+
+    if !codes.is_empty() {
+        tx.execute(
+            "UPDATE gpkg_contents SET min_x = ?1, min_y = ?2, max_x = ?3, max_y = ?4
+            WHERE table_name = 'meshcodes'",
+            params![bbox.0, bbox.1, bbox.2, bbox.3],
+        )?;
+    }
+
+    tx.commit()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_f64(buf: &[u8], offset: usize) -> f64 {
+        f64::from_le_bytes(buf[offset..offset + 8].try_into().unwrap())
+    }
+
+    #[test]
+    fn test_write_geopackage() {
+        let path = std::env::temp_dir().join(format!(
+            "jismesh_test_write_geopackage_{}.gpkg",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+
+        let codes: Vec<MeshCode> = [53393599, 53393598, 53394509, 533935, 5339]
+            .iter()
+            .map(|&v| MeshCode::try_from(v).unwrap())
+            .collect();
+        write_geopackage(&path, &codes).unwrap();
+
+        let conn = Connection::open(&path).unwrap();
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM meshcodes", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 5);
+
+        let (geom, meshcode, level): (Vec<u8>, i64, String) = conn
+            .query_row(
+                "SELECT geom, meshcode, level FROM meshcodes ORDER BY fid LIMIT 1",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(meshcode, 53393599);
+        assert_eq!(level, "Lv3");
+        assert_eq!(&geom[..2], b"GP");
+
+        // The header envelope is [minx, maxx, miny, maxy]
+        let envelope = (
+            read_f64(&geom, 8),
+            read_f64(&geom, 16),
+            read_f64(&geom, 24),
+            read_f64(&geom, 32),
+        );
+        assert_eq!(
+            envelope,
+            (139.7375, 139.75, 35.65833333333333, 35.666666666666664)
+        );
+
+        // Writing the same table twice is an error
+        assert!(write_geopackage(&path, &codes).is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,1345 @@
+use super::*;
+use core::ops::ControlFlow;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+/// 隣接するメッシュの方角
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter)]
+pub enum Direction {
+    /// 北
+    N,
+    /// 南
+    S,
+    /// 東
+    E,
+    /// 西
+    W,
+    /// 北東
+    NE,
+    /// 北西
+    NW,
+    /// 南東
+    SE,
+    /// 南西
+    SW,
+}
+
+impl Direction {
+    /// 方角を (行, 列) の差分として取得する。北と東が正の方向です。
+    pub fn offset(&self) -> (i64, i64) {
+        match self {
+            Direction::N => (1, 0),
+            Direction::S => (-1, 0),
+            Direction::E => (0, 1),
+            Direction::W => (0, -1),
+            Direction::NE => (1, 1),
+            Direction::NW => (1, -1),
+            Direction::SE => (-1, 1),
+            Direction::SW => (-1, -1),
+        }
+    }
+}
+
+/// メッシュを 2×2 に分割した際の位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter)]
+pub enum Quadrant {
+    /// 南西
+    SW,
+    /// 南東
+    SE,
+    /// 北西
+    NW,
+    /// 北東
+    NE,
+}
+
+/// Number of meshes of `level` along one side of a Lv1 mesh.
+pub(crate) fn cells_per_lv1(level: MeshLevel) -> u64 {
+    match level {
+        MeshLevel::Lv1 => 1,
+        MeshLevel::X40 => 2,
+        MeshLevel::X20 => 4,
+        MeshLevel::X16 => 5,
+        MeshLevel::Lv2 => 8,
+        MeshLevel::X8 => 10,
+        MeshLevel::X5 => 16,
+        MeshLevel::X4 => 20,
+        MeshLevel::X2_5 => 32,
+        MeshLevel::X2 => 40,
+        MeshLevel::Lv3 => 80,
+        MeshLevel::Lv4 => 160,
+        MeshLevel::Lv5 => 320,
+        MeshLevel::Lv6 => 640,
+    }
+}
+
+/// Decodes a 2x2 quadrant digit (1: SW, 2: SE, 3: NW, 4: NE) into (row, col) offsets.
+fn quadrant_offset(digit: u64) -> (u64, u64) {
+    ((digit / 3 == 1) as u64, digit.is_multiple_of(2) as u64)
+}
+
+/// Encodes (row, col) offsets within a 2x2 subdivision into a quadrant digit.
+fn quadrant_digit(row: u64, col: u64) -> u64 {
+    row * 2 + col + 1
+}
+
+/// Calculates the global (row, col) index of a mesh code from its digits.
+fn decode_index(value: u64, level: MeshLevel) -> (u64, u64) {
+    let join = |(row, col): (u64, u64), factor: u64, (sub_row, sub_col): (u64, u64)| {
+        (row * factor + sub_row, col * factor + sub_col)
+    };
+    match level {
+        MeshLevel::Lv1 => (value / 100, value % 100),
+        MeshLevel::X40 => join(
+            decode_index(value / 10, MeshLevel::Lv1),
+            2,
+            quadrant_offset(value % 10),
+        ),
+        MeshLevel::X20 => join(
+            decode_index(value / 100, MeshLevel::X40),
+            2,
+            quadrant_offset(value / 10 % 10),
+        ),
+        MeshLevel::X16 => join(
+            decode_index(value / 1000, MeshLevel::Lv1),
+            5,
+            (value / 100 % 10 / 2, value / 10 % 10 / 2),
+        ),
+        MeshLevel::Lv2 => join(
+            decode_index(value / 100, MeshLevel::Lv1),
+            8,
+            (value / 10 % 10, value % 10),
+        ),
+        MeshLevel::X8 => join(
+            decode_index(value / 1000, MeshLevel::Lv1),
+            10,
+            (value / 100 % 10, value / 10 % 10),
+        ),
+        MeshLevel::X5 => join(
+            decode_index(value / 10, MeshLevel::Lv2),
+            2,
+            quadrant_offset(value % 10),
+        ),
+        MeshLevel::X4 => join(
+            decode_index(value / 100, MeshLevel::X8),
+            2,
+            quadrant_offset(value / 10 % 10),
+        ),
+        MeshLevel::X2_5 => join(
+            decode_index(value / 100, MeshLevel::X5),
+            2,
+            quadrant_offset(value / 10 % 10),
+        ),
+        MeshLevel::X2 => join(
+            decode_index(value / 1000, MeshLevel::Lv2),
+            5,
+            (value / 100 % 10 / 2, value / 10 % 10 / 2),
+        ),
+        MeshLevel::Lv3 => join(
+            decode_index(value / 100, MeshLevel::Lv2),
+            10,
+            (value / 10 % 10, value % 10),
+        ),
+        MeshLevel::Lv4 => join(
+            decode_index(value / 10, MeshLevel::Lv3),
+            2,
+            quadrant_offset(value % 10),
+        ),
+        MeshLevel::Lv5 => join(
+            decode_index(value / 10, MeshLevel::Lv4),
+            2,
+            quadrant_offset(value % 10),
+        ),
+        MeshLevel::Lv6 => join(
+            decode_index(value / 10, MeshLevel::Lv5),
+            2,
+            quadrant_offset(value % 10),
+        ),
+    }
+}
+
+/// Builds the mesh code digits for a global (row, col) index.
+/// The index must already be validated to be in range.
+fn encode_index(row: u64, col: u64, level: MeshLevel) -> u64 {
+    match level {
+        MeshLevel::Lv1 => row * 100 + col,
+        MeshLevel::X40 => {
+            encode_index(row / 2, col / 2, MeshLevel::Lv1) * 10 + quadrant_digit(row % 2, col % 2)
+        }
+        MeshLevel::X20 => {
+            encode_index(row / 2, col / 2, MeshLevel::X40) * 100
+                + quadrant_digit(row % 2, col % 2) * 10
+                + 5
+        }
+        MeshLevel::X16 => {
+            encode_index(row / 5, col / 5, MeshLevel::Lv1) * 1000
+                + (row % 5 * 2) * 100
+                + (col % 5 * 2) * 10
+                + 7
+        }
+        MeshLevel::Lv2 => {
+            encode_index(row / 8, col / 8, MeshLevel::Lv1) * 100 + (row % 8) * 10 + col % 8
+        }
+        MeshLevel::X8 => {
+            encode_index(row / 10, col / 10, MeshLevel::Lv1) * 1000
+                + (row % 10) * 100
+                + (col % 10) * 10
+                + 6
+        }
+        MeshLevel::X5 => {
+            encode_index(row / 2, col / 2, MeshLevel::Lv2) * 10 + quadrant_digit(row % 2, col % 2)
+        }
+        MeshLevel::X4 => {
+            encode_index(row / 2, col / 2, MeshLevel::X8) * 100
+                + quadrant_digit(row % 2, col % 2) * 10
+                + 7
+        }
+        MeshLevel::X2_5 => {
+            encode_index(row / 2, col / 2, MeshLevel::X5) * 100
+                + quadrant_digit(row % 2, col % 2) * 10
+                + 6
+        }
+        MeshLevel::X2 => {
+            encode_index(row / 5, col / 5, MeshLevel::Lv2) * 1000
+                + (row % 5 * 2) * 100
+                + (col % 5 * 2) * 10
+                + 5
+        }
+        MeshLevel::Lv3 => {
+            encode_index(row / 10, col / 10, MeshLevel::Lv2) * 100 + (row % 10) * 10 + col % 10
+        }
+        MeshLevel::Lv4 => {
+            encode_index(row / 2, col / 2, MeshLevel::Lv3) * 10 + quadrant_digit(row % 2, col % 2)
+        }
+        MeshLevel::Lv5 => {
+            encode_index(row / 2, col / 2, MeshLevel::Lv4) * 10 + quadrant_digit(row % 2, col % 2)
+        }
+        MeshLevel::Lv6 => {
+            encode_index(row / 2, col / 2, MeshLevel::Lv5) * 10 + quadrant_digit(row % 2, col % 2)
+        }
+    }
+}
+
+impl MeshCode {
+    /// メッシュの全体グリッド上の位置 (行, 列) を取得する。
+    ///
+    /// 行は緯度 0 度から北向き、列は経度 100 度から東向きに、同じ次数のメッシュを数えた番号です。
+    /// 同じ次数のメッシュ同士であれば、行・列の差がそのままメッシュ数の差になります。
+    pub fn global_index(&self) -> (u64, u64) {
+        decode_index(self.value, self.level)
+    }
+
+    /// 全体グリッド上の位置 (行, 列) からメッシュコードを生成する。 `global_index` の逆変換です。
+    pub fn from_global_index(row: u64, col: u64, level: MeshLevel) -> Result<MeshCode> {
+        let cells = cells_per_lv1(level);
+        if row >= 100 * cells || col >= 80 * cells {
+            return Err(JismeshError::GlobalIndexOutOfBounds(level, row, col));
+        }
+        Ok(MeshCode {
+            value: encode_index(row, col, level),
+            level,
+        })
+    }
+
+    /// The mesh as a half-open (row_s, col_w, row_n, col_e) rectangle of Lv6 global indexes.
+    /// Every level divides Lv1 evenly into Lv6 cells, so this is exact for all levels.
+    pub(crate) fn lv6_rect(&self) -> (u64, u64, u64, u64) {
+        let scale = cells_per_lv1(MeshLevel::Lv6) / cells_per_lv1(self.level);
+        let (row, col) = self.global_index();
+        (
+            row * scale,
+            col * scale,
+            (row + 1) * scale,
+            (col + 1) * scale,
+        )
+    }
+
+    /// このメッシュが、指定された次数のメッシュ 1 つに完全に収まるかどうかを確認する。
+    ///
+    /// 指定次数のメッシュの一辺が、このメッシュの一辺の整数倍であれば、境界がそろうので
+    /// 集計時に面積按分なしで親メッシュへまとめられます（例: Lv3 → Lv2 、 Lv3 → X2）。
+    /// 指定次数の方が細かい場合は false です。
+    pub fn can_nest_into(&self, level: MeshLevel) -> bool {
+        cells_per_lv1(self.level).is_multiple_of(cells_per_lv1(level))
+    }
+
+    /// 同じ次数で、指定された行・列数だけ移動したメッシュコードを取得する。
+    /// 行は北向き、列は東向きが正です。1次メッシュの境界をまたいでも正しく計算されます。
+    ///
+    /// 移動先が有効な範囲（緯度 0〜66.66 度、経度 100〜180 度）外になる場合はエラーを返します。
+    pub fn step(&self, d_row: i64, d_col: i64) -> Result<MeshCode> {
+        let out_of_bounds = || JismeshError::StepOutOfBounds(self.value, d_row, d_col);
+        let (row, col) = self.global_index();
+        let new_row = (row as i64).checked_add(d_row).ok_or_else(out_of_bounds)?;
+        let new_col = (col as i64).checked_add(d_col).ok_or_else(out_of_bounds)?;
+        if new_row < 0 || new_col < 0 {
+            return Err(out_of_bounds());
+        }
+        MeshCode::from_global_index(new_row as u64, new_col as u64, self.level)
+            .map_err(|_| out_of_bounds())
+    }
+
+    /// 指定した方角に隣接する、同じ次数のメッシュコードを取得する。
+    /// 1次メッシュの境界をまたいでも正しく計算されます。
+    ///
+    /// 隣接メッシュが有効な範囲（緯度 0〜66.66 度、経度 100〜180 度）外になる場合はエラーを返します。
+    pub fn neighbor(&self, direction: Direction) -> Result<MeshCode> {
+        let (d_row, d_col) = direction.offset();
+        self.step(d_row, d_col)
+    }
+
+    /// 周囲 8 方向に隣接する、同じ次数のメッシュコードを取得する。
+    /// 順番は `Direction::iter()` の順で、有効な範囲外になる方角は含まれません。
+    pub fn neighbors(&self) -> Vec<MeshCode> {
+        Direction::iter()
+            .filter_map(|direction| self.neighbor(direction).ok())
+            .collect()
+    }
+
+    /// このメッシュから、同じ次数の other までの行・列数 (d_row, d_col) を取得する。
+    /// 行は北向き、列は東向きが正で、 `step` の逆です（`self.step(d_row, d_col)` が other になります）。
+    ///
+    /// 次数が異なる場合はエラーを返します。
+    pub fn cell_offset(&self, other: &MeshCode) -> Result<(i64, i64)> {
+        if self.level != other.level {
+            return Err(JismeshError::MismatchedMeshLevels(self.level, other.level));
+        }
+        let (row_a, col_a) = self.global_index();
+        let (row_b, col_b) = other.global_index();
+        Ok((row_b as i64 - row_a as i64, col_b as i64 - col_a as i64))
+    }
+
+    /// 同じ次数のメッシュコードが、行・列方向に max_cell_distance メッシュ以内（チェビシェフ距離）にあるかを判定する。
+    /// 測地系の変換などで再計算したメッシュコードと、保存済みのメッシュコードの照合に使えます。
+    ///
+    /// 次数が異なる場合は false を返します。
+    pub fn approx_equals(&self, other: &MeshCode, max_cell_distance: u32) -> bool {
+        if self.level != other.level {
+            return false;
+        }
+        let (row_a, col_a) = self.global_index();
+        let (row_b, col_b) = other.global_index();
+        row_a.abs_diff(row_b).max(col_a.abs_diff(col_b)) <= max_cell_distance as u64
+    }
+
+    /// このメッシュから other まで、中心点同士を結ぶ直線に沿った同じ次数のメッシュコードを取得する。
+    ///
+    /// 結果は self から other までを両端を含めて順に並べたもので、隣り合うメッシュは
+    /// 必ず辺または角で接します（Bresenham のアルゴリズム）。
+    ///
+    /// 次数が異なる場合はエラーを返します。
+    pub fn walk_to(&self, other: &MeshCode) -> Result<Vec<MeshCode>> {
+        if self.level != other.level {
+            return Err(JismeshError::MismatchedMeshLevels(self.level, other.level));
+        }
+        let (row0, col0) = self.global_index();
+        let (row1, col1) = other.global_index();
+        let (mut row, mut col) = (row0 as i64, col0 as i64);
+        let (row1, col1) = (row1 as i64, col1 as i64);
+
+        let d_row = (row1 - row).abs();
+        let d_col = (col1 - col).abs();
+        let s_row = if row < row1 { 1 } else { -1 };
+        let s_col = if col < col1 { 1 } else { -1 };
+        let mut err = d_col - d_row;
+
+        let mut path = Vec::with_capacity(d_row.max(d_col) as usize + 1);
+        loop {
+            path.push(MeshCode::from_global_index(
+                row as u64, col as u64, self.level,
+            )?);
+            if row == row1 && col == col1 {
+                break;
+            }
+            let e2 = 2 * err;
+            if e2 > -d_row {
+                err -= d_row;
+                col += s_col;
+            }
+            if e2 < d_col {
+                err += d_col;
+                row += s_row;
+            }
+        }
+        Ok(path)
+    }
+
+    /// このメッシュを中心に、行・列方向に `radius` 以内の同じ次数のメッシュコードを取得する。
+    /// 結果は一辺 2 * radius + 1 の正方形を埋める (2 * radius + 1)² 個で、南西から北東へ
+    /// 行ごとに並びます。
+    ///
+    /// 有効な範囲外にはみ出すメッシュがある場合はエラーを返します。
+    pub fn square(&self, radius: u32) -> Result<Vec<MeshCode>> {
+        let r = radius as i64;
+        // The square fits in the grid only if both corners do, which also bounds the capacity
+        self.step(-r, -r)?;
+        self.step(r, r)?;
+
+        let mut codes = Vec::with_capacity((2 * radius as usize + 1).pow(2));
+        for d_row in -r..=r {
+            for d_col in -r..=r {
+                codes.push(self.step(d_row, d_col)?);
+            }
+        }
+        Ok(codes)
+    }
+
+    /// `square` と同じですが、有効な範囲外のメッシュや、中心点が日本の範囲
+    /// （`codes::JAPAN_BBOX`）外になるメッシュを除外します。エラーを返しません。
+    pub fn square_clamped(&self, radius: u32) -> Vec<MeshCode> {
+        let cells = cells_per_lv1(self.level) as i64;
+        let (row, col) = self.global_index();
+        let (row, col) = (row as i64, col as i64);
+        let r = radius as i64;
+
+        let mut codes = Vec::new();
+        for d_row in -r.min(row)..=r.min(100 * cells - 1 - row) {
+            for d_col in -r.min(col)..=r.min(80 * cells - 1 - col) {
+                if let Ok(code) = self.step(d_row, d_col)
+                    && code.is_within_japan()
+                {
+                    codes.push(code);
+                }
+            }
+        }
+        codes
+    }
+
+    /// 外部システム向けの、位置順に並ぶ 64bit の空間キーを取得する。
+    ///
+    /// 上位 8bit が次数のタグ、下位 32bit が全体グリッド上の行・列をビットごとに交互に並べた
+    /// Morton (Z-order) 符号です。同じ次数であれば、近いメッシュは近いキーになります。
+    /// `from_spatial_key` で元のメッシュコードに戻せます。
+    pub fn spatial_key(&self) -> u64 {
+        let (row, col) = self.global_index();
+        (level_tag(self.level) << 56) | (spread_bits(row) << 1) | spread_bits(col)
+    }
+
+    /// `spatial_key` で取得した空間キーからメッシュコードを生成する。
+    pub fn from_spatial_key(key: u64) -> Result<MeshCode> {
+        let level = MeshLevel::iter()
+            .find(|level| level_tag(*level) == key >> 56)
+            .ok_or(JismeshError::InvalidSpatialKey(key))?;
+        if key & 0x00ff_ffff_0000_0000 != 0 {
+            return Err(JismeshError::InvalidSpatialKey(key));
+        }
+        MeshCode::from_global_index(compact_bits(key >> 1), compact_bits(key), level)
+            .map_err(|_| JismeshError::InvalidSpatialKey(key))
+    }
+
+    /// このメッシュに含まれる、指定次数の子孫メッシュコードの値の最小値と最大値を取得する。
+    /// 返却値は (最小値, 最大値) です。
+    ///
+    /// メッシュコードの数値の順番は位置の順番と一致しないため、角のメッシュではなく、
+    /// 全ての子孫メッシュの値から求めます。数値キーでの範囲スキャン（`min..=max`）に使えます。
+    ///
+    /// 指定次数がこのメッシュより粗い場合や、このメッシュにぴったり収まらない次数の場合は
+    /// エラーを返します。
+    pub fn code_value_range(&self, level: MeshLevel) -> Result<(u64, u64)> {
+        let mut values = self.descendants(level)?.map(|code| code.value);
+        // a valid descendant level always yields at least one code (the mesh itself)
+        let first = values.next().unwrap();
+        Ok(values.fold((first, first), |(min, max), value| {
+            (min.min(value), max.max(value))
+        }))
+    }
+
+    /// このメッシュを 2×2 に分割した子メッシュコードを、位置（南西・南東・北西・北東）ごとに取得する。
+    ///
+    /// 指定次数が、このメッシュをちょうど半分に分割する次数（Lv3 → Lv4、 Lv2 → X5 など）で
+    /// ない場合はエラーを返します。
+    #[cfg(feature = "std")]
+    pub fn quadrants(&self, level: MeshLevel) -> Result<HashMap<Quadrant, MeshCode>> {
+        if level <= self.level {
+            return Err(JismeshError::InvalidMeshLevelForHigherLevel(
+                level, self.level,
+            ));
+        }
+        if cells_per_lv1(level) != cells_per_lv1(self.level) * 2 {
+            return Err(JismeshError::UnsupportedMeshLevelConversion(
+                self.level, level,
+            ));
+        }
+        // descendants are in row-major order from the south-west, same as `Quadrant`
+        Ok(Quadrant::iter().zip(self.descendants(level)?).collect())
+    }
+
+    /// このメッシュの外側で、このメッシュに接する指定次数のメッシュコードを取得する。
+    /// 例えば、2次メッシュの周囲を囲む 3次メッシュ 44 個を取得できます。
+    /// 角で接するメッシュも含み、結果は南西から北東へ行ごとに並びます。
+    ///
+    /// 有効な範囲（緯度 0〜66.66 度、経度 100〜180 度）外のメッシュは除外されます。
+    /// 指定次数がこのメッシュより粗い場合や、このメッシュにぴったり収まらない次数の場合は
+    /// エラーを返します。
+    pub fn border_cells(&self, level: MeshLevel) -> Result<Vec<MeshCode>> {
+        let inner = self.descendants(level)?;
+        let (sw, ne) = ((inner.row, inner.col_w), (inner.row_n, inner.col_e));
+
+        let cells = cells_per_lv1(level);
+        let outer_sw = (sw.0.saturating_sub(1), sw.1.saturating_sub(1));
+        let outer_ne = (
+            (ne.0 + 1).min(100 * cells - 1),
+            (ne.1 + 1).min(80 * cells - 1),
+        );
+        Ok(GridIter::new(level, outer_sw, outer_ne)
+            .filter(|code| {
+                let (row, col) = code.global_index();
+                !(sw.0 <= row && row <= ne.0 && sw.1 <= col && col <= ne.1)
+            })
+            .collect())
+    }
+
+    /// このメッシュに含まれる、指定次数の全ての子孫メッシュコードを取得する。
+    /// 例: 2次メッシュから3次メッシュは 100 個、1次メッシュから2次メッシュは 64 個です。
+    ///
+    /// 全体グリッド上の行・列から求めるため、境界に接するだけのメッシュは含まれません。
+    /// 結果は南西から北東へ行ごとに並びます。
+    ///
+    /// 指定次数がこのメッシュと同じか粗い場合や、このメッシュにぴったり収まらない次数の場合は
+    /// エラーを返します。
+    pub fn children(&self, level: MeshLevel) -> Result<Vec<MeshCode>> {
+        if level <= self.level {
+            return Err(JismeshError::InvalidMeshLevelForHigherLevel(
+                level, self.level,
+            ));
+        }
+        Ok(self.descendants(level)?.collect())
+    }
+
+    /// このメッシュに含まれる指定次数の子孫メッシュコードを、1つずつ f に渡す。
+    ///
+    /// 子孫メッシュは全体グリッド上の行・列から順に生成されるため、メモリを確保しません。
+    /// f が `ControlFlow::Break` を返すと、その時点で生成を止めて `ControlFlow::Break(())` を返します。
+    /// 生成順は南西から北東へ行ごとです。
+    ///
+    /// 指定次数がこのメッシュより粗い場合や、このメッシュにぴったり収まらない次数の場合は
+    /// エラーを返します。
+    pub fn for_each_child(
+        &self,
+        level: MeshLevel,
+        mut f: impl FnMut(MeshCode) -> ControlFlow<()>,
+    ) -> Result<ControlFlow<()>> {
+        for child in self.descendants(level)? {
+            if f(child).is_break() {
+                return Ok(ControlFlow::Break(()));
+            }
+        }
+        Ok(ControlFlow::Continue(()))
+    }
+
+    /// 指定次数で、このメッシュと重なるメッシュコードを1つずつ生成するイテレータを取得する。
+    ///
+    /// `to_intersects` と同じメッシュコードを同じ順番（南西から北東へ行ごと）で返しますが、
+    /// 全体グリッド上の行・列から順に生成するため、 Vec を確保しません。
+    /// 1次メッシュから6次メッシュのような、非常に多くのメッシュコードを処理する場合に使ってください。
+    pub fn intersects_iter(&self, to_level: MeshLevel) -> Result<impl Iterator<Item = MeshCode>> {
+        // A coarser level that this mesh nests into has exactly one mesh code containing it
+        if to_level < self.level && self.can_nest_into(to_level) {
+            let index = self.lower_level(to_level)?.global_index();
+            return Ok(GridIter::new(to_level, index, index));
+        }
+        let (lat_s, lon_w, lat_n, lon_e) = self.bounds()?;
+        Ok(GridIter::from_bbox(to_level, lat_s, lon_w, lat_n, lon_e))
+    }
+
+    /// Iterates over every mesh code of `level` inside this mesh. `level` must be this
+    /// mesh's level or a finer level whose cells nest into this mesh.
+    pub(crate) fn descendants(&self, level: MeshLevel) -> Result<GridIter> {
+        if unit_lat(level) > unit_lat(self.level) {
+            return Err(JismeshError::InvalidMeshLevelForHigherLevel(
+                level, self.level,
+            ));
+        }
+        let cells = cells_per_lv1(level);
+        let parent_cells = cells_per_lv1(self.level);
+        if !cells.is_multiple_of(parent_cells) {
+            return Err(JismeshError::UnsupportedMeshLevelConversion(
+                self.level, level,
+            ));
+        }
+
+        let ratio = cells / parent_cells;
+        let (row, col) = self.global_index();
+        Ok(GridIter::new(
+            level,
+            (row * ratio, col * ratio),
+            (row * ratio + ratio - 1, col * ratio + ratio - 1),
+        ))
+    }
+}
+
+/// Fixed tag of each level for `MeshCode::spatial_key`, in order of size.
+/// These values are part of the key format and must not change.
+fn level_tag(level: MeshLevel) -> u64 {
+    match level {
+        MeshLevel::Lv1 => 1,
+        MeshLevel::X40 => 2,
+        MeshLevel::X20 => 3,
+        MeshLevel::X16 => 4,
+        MeshLevel::Lv2 => 5,
+        MeshLevel::X8 => 6,
+        MeshLevel::X5 => 7,
+        MeshLevel::X4 => 8,
+        MeshLevel::X2_5 => 9,
+        MeshLevel::X2 => 10,
+        MeshLevel::Lv3 => 11,
+        MeshLevel::Lv4 => 12,
+        MeshLevel::Lv5 => 13,
+        MeshLevel::Lv6 => 14,
+    }
+}
+
+/// Spreads the lower 16 bits of `value` to the even bits of the result.
+/// Global indexes are below 2^16 at every level (Lv6, the finest level, has 64000 rows and
+/// 51200 columns), so the interleaved code fits in 32 bits and `u64` keys never overflow.
+fn spread_bits(value: u64) -> u64 {
+    let mut x = value & 0xffff;
+    x = (x | (x << 8)) & 0x00ff_00ff;
+    x = (x | (x << 4)) & 0x0f0f_0f0f;
+    x = (x | (x << 2)) & 0x3333_3333;
+    (x | (x << 1)) & 0x5555_5555
+}
+
+/// Inverse of `spread_bits`: gathers the even bits of the lower 32 bits of `value`.
+fn compact_bits(value: u64) -> u64 {
+    let mut x = value & 0x5555_5555;
+    x = (x | (x >> 1)) & 0x3333_3333;
+    x = (x | (x >> 2)) & 0x0f0f_0f0f;
+    x = (x | (x >> 4)) & 0x00ff_00ff;
+    (x | (x >> 8)) & 0xffff
+}
+
+/// Lazily walks every mesh code of a level in an inclusive global index rectangle,
+/// in row-major order from south-west to north-east.
+pub(crate) struct GridIter {
+    level: MeshLevel,
+    row: u64,
+    col: u64,
+    row_n: u64,
+    col_w: u64,
+    col_e: u64,
+}
+
+impl GridIter {
+    /// `sw` and `ne` are inclusive (row, col) global indexes, which must be in range for `level`.
+    pub(crate) fn new(level: MeshLevel, sw: (u64, u64), ne: (u64, u64)) -> Self {
+        GridIter {
+            level,
+            row: sw.0,
+            col: sw.1,
+            row_n: ne.0,
+            col_w: sw.1,
+            col_e: ne.1,
+        }
+    }
+
+    /// Walks the mesh codes of `level` overlapping the interior of a bounding box,
+    /// clipped to the valid range.
+    pub(crate) fn from_bbox(
+        level: MeshLevel,
+        lat_s: f64,
+        lon_w: f64,
+        lat_n: f64,
+        lon_e: f64,
+    ) -> Self {
+        // A small tolerance keeps boundaries that fall exactly on a grid line from picking
+        // up an extra row or column.
+        const EPS: f64 = 1e-9;
+        let cells = cells_per_lv1(level);
+        let first = |x: f64, max: u64| (x + EPS).floor().clamp(0.0, (max - 1) as f64) as u64;
+        let last = |x: f64, max: u64| ((x - EPS).ceil() - 1.0).clamp(0.0, (max - 1) as f64) as u64;
+
+        let (row_s, row_n) = (lat_s / unit_lat(level), lat_n / unit_lat(level));
+        let col_w = (lon_w - 100.0) / unit_lon(level);
+        let col_e = (lon_e - 100.0) / unit_lon(level);
+        GridIter::new(
+            level,
+            (first(row_s, 100 * cells), first(col_w, 80 * cells)),
+            (last(row_n, 100 * cells), last(col_e, 80 * cells)),
+        )
+    }
+
+    /// Total number of mesh codes in the rectangle.
+    pub(crate) fn total(&self) -> u64 {
+        if self.row > self.row_n || self.col_w > self.col_e {
+            return 0;
+        }
+        (self.row_n - self.row + 1) * (self.col_e - self.col_w + 1)
+    }
+}
+
+impl Iterator for GridIter {
+    type Item = MeshCode;
+
+    fn next(&mut self) -> Option<MeshCode> {
+        if self.row > self.row_n || self.col_w > self.col_e {
+            return None;
+        }
+        let code = MeshCode {
+            value: encode_index(self.row, self.col, self.level),
+            level: self.level,
+        };
+        if self.col == self.col_e {
+            self.col = self.col_w;
+            self.row += 1;
+        } else {
+            self.col += 1;
+        }
+        Some(code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_global_index() {
+        let meshcode = MeshCode::try_from(5339).unwrap();
+        assert_eq!(meshcode.global_index(), (53, 39));
+
+        let meshcode = MeshCode::try_from(53393599).unwrap();
+        assert_eq!(meshcode.global_index(), (53 * 80 + 39, 39 * 80 + 59));
+
+        // Neighboring meshes differ by one column
+        let west = MeshCode::try_from(53393598).unwrap().global_index();
+        let east = MeshCode::try_from(53393599).unwrap().global_index();
+        assert_eq!((east.0 - west.0, east.1 - west.1), (0, 1));
+
+        // Crossing a Lv2 boundary to the north
+        let south = MeshCode::try_from(53393599).unwrap().global_index();
+        let north = MeshCode::try_from(53394509).unwrap().global_index();
+        assert_eq!((north.0 - south.0, north.1 - south.1), (1, 0));
+    }
+
+    #[test]
+    fn test_global_index_matches_point() {
+        let lat = 35.658581;
+        let lon = 139.745433;
+        for level in MeshLevel::iter() {
+            let meshcode = MeshCode::try_from_latlng(lat, lon, level).unwrap();
+            let (row, col) = meshcode.global_index();
+            let (lat_s, lon_w) = meshcode.point(0.0, 0.0).unwrap();
+            assert_eq!(row, (lat_s / unit_lat(level)).round() as u64, "{}", level);
+            assert_eq!(
+                col,
+                ((lon_w - 100.0) / unit_lon(level)).round() as u64,
+                "{}",
+                level
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_global_index_round_trip() {
+        let points = [(35.658581, 139.745433), (34.987574, 135.759363)];
+        for (lat, lon) in points {
+            for level in MeshLevel::iter() {
+                let meshcode = MeshCode::try_from_latlng(lat, lon, level).unwrap();
+                let (row, col) = meshcode.global_index();
+                assert_eq!(
+                    MeshCode::from_global_index(row, col, level),
+                    Ok(meshcode),
+                    "Failed for {}",
+                    meshcode
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_global_index_far_corner() {
+        // The north-east corner of the domain has the largest indexes at every level
+        for level in MeshLevel::iter() {
+            let cells = cells_per_lv1(level);
+            let (row, col) = (100 * cells - 1, 80 * cells - 1);
+            let meshcode = MeshCode::from_global_index(row, col, level).unwrap();
+            assert_eq!(meshcode.global_index(), (row, col), "Failed for {}", level);
+            assert_eq!(
+                MeshCode::try_from(u64::from(meshcode)),
+                Ok(meshcode),
+                "Failed for {}",
+                level
+            );
+            assert_eq!(
+                MeshCode::from_spatial_key(meshcode.spatial_key()),
+                Ok(meshcode),
+                "Failed for {}",
+                level
+            );
+        }
+
+        let meshcode = MeshCode::from_global_index(63999, 51199, MeshLevel::Lv6).unwrap();
+        assert_eq!(meshcode, 99797799444);
+        let (lat_n, lon_e) = meshcode.point(1.0, 1.0).unwrap();
+        assert!((lat_n - 100.0 * 2.0 / 3.0).abs() < 1e-9);
+        assert!((lon_e - 180.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_can_nest_into() {
+        let lv3 = MeshCode::try_from(53393599).unwrap();
+        assert!(lv3.can_nest_into(MeshLevel::Lv3));
+        assert!(lv3.can_nest_into(MeshLevel::Lv2));
+        assert!(lv3.can_nest_into(MeshLevel::Lv1));
+        assert!(lv3.can_nest_into(MeshLevel::X2));
+        assert!(lv3.can_nest_into(MeshLevel::X5));
+        assert!(lv3.can_nest_into(MeshLevel::X20));
+        assert!(!lv3.can_nest_into(MeshLevel::X2_5));
+        assert!(!lv3.can_nest_into(MeshLevel::Lv4));
+
+        let x2_5 = MeshCode::try_from(533935446).unwrap();
+        assert!(!x2_5.can_nest_into(MeshLevel::Lv3));
+        assert!(!x2_5.can_nest_into(MeshLevel::X8));
+        assert!(x2_5.can_nest_into(MeshLevel::X5));
+        assert!(x2_5.can_nest_into(MeshLevel::Lv2));
+    }
+
+    #[test]
+    fn test_step() {
+        let meshcode = MeshCode::try_from(53393599).unwrap();
+        assert_eq!(meshcode.step(0, 0).unwrap(), meshcode);
+        assert_eq!(meshcode.step(0, -1).unwrap(), 53393598);
+        assert_eq!(meshcode.step(-1, 0).unwrap(), 53393589);
+
+        // Crossing Lv2 and Lv1 boundaries carries into the upper digits
+        assert_eq!(meshcode.step(1, 0).unwrap(), 53394509);
+        assert_eq!(meshcode.step(0, 1).unwrap(), 53393690);
+        let meshcode = MeshCode::try_from(5339).unwrap();
+        assert_eq!(meshcode.step(1, 1).unwrap(), 5440);
+
+        let (d_row, d_col) = Direction::SW.offset();
+        assert_eq!(meshcode.step(d_row, d_col).unwrap(), 5238);
+    }
+
+    #[test]
+    fn test_neighbor() {
+        let meshcode = MeshCode::try_from(53393599).unwrap();
+        assert_eq!(meshcode.neighbor(Direction::W).unwrap(), 53393598);
+        assert_eq!(meshcode.neighbor(Direction::S).unwrap(), 53393589);
+        assert_eq!(meshcode.neighbor(Direction::E).unwrap(), 53393690);
+        assert_eq!(meshcode.neighbor(Direction::N).unwrap(), 53394509);
+        assert_eq!(meshcode.neighbor(Direction::NE).unwrap(), 53394600);
+
+        // Crossing a Lv1 boundary carries into the ab / cd digits
+        let meshcode = MeshCode::try_from(53397799).unwrap();
+        assert_eq!(meshcode.neighbor(Direction::E).unwrap(), 53407090);
+        assert_eq!(meshcode.neighbor(Direction::N).unwrap(), 54390709);
+        assert_eq!(meshcode.neighbor(Direction::NE).unwrap(), 54400000);
+        let meshcode = MeshCode::try_from(5339).unwrap();
+        assert_eq!(meshcode.neighbor(Direction::SW).unwrap(), 5238);
+
+        // Running off the valid range is an error, not a wrapped code
+        let meshcode = MeshCode::try_from(5379).unwrap();
+        assert_eq!(
+            meshcode.neighbor(Direction::E),
+            Err(JismeshError::StepOutOfBounds(5379, 0, 1))
+        );
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let meshcode = MeshCode::try_from(53393599).unwrap();
+        let neighbors = meshcode.neighbors();
+        assert_eq!(
+            neighbors,
+            vec![
+                53394509, 53393589, 53393690, 53393598, 53394600, 53394508, 53393680, 53393588
+            ]
+        );
+        assert!(
+            neighbors
+                .iter()
+                .all(|code| code.approx_equals(&meshcode, 1))
+        );
+
+        // On the edge of the valid range only the neighbors inside are returned
+        let meshcode = MeshCode::try_from(5379).unwrap();
+        assert_eq!(meshcode.neighbors(), vec![5479, 5279, 5378, 5478, 5278]);
+    }
+
+    #[test]
+    fn test_step_out_of_bounds() {
+        let meshcode = MeshCode {
+            value: 39,
+            level: MeshLevel::Lv1,
+        };
+        assert_eq!(
+            meshcode.step(-1, 0),
+            Err(JismeshError::StepOutOfBounds(39, -1, 0))
+        );
+        let meshcode = MeshCode::try_from(5379).unwrap();
+        assert_eq!(
+            meshcode.step(0, 1),
+            Err(JismeshError::StepOutOfBounds(5379, 0, 1))
+        );
+
+        // Offsets at the limits of i64 are out of bounds rather than overflowing
+        let meshcode = MeshCode::try_from(53393599).unwrap();
+        for (d_row, d_col) in [(i64::MAX, 0), (i64::MIN, 0), (0, i64::MAX), (0, i64::MIN)] {
+            assert_eq!(
+                meshcode.step(d_row, d_col),
+                Err(JismeshError::StepOutOfBounds(53393599, d_row, d_col))
+            );
+        }
+    }
+
+    #[test]
+    fn test_cell_offset() {
+        let a = MeshCode::try_from(53393599).unwrap();
+        let b = a.step(3, -2).unwrap();
+        assert_eq!(a.cell_offset(&b).unwrap(), (3, -2));
+        assert_eq!(b.cell_offset(&a).unwrap(), (-3, 2));
+        assert_eq!(a.cell_offset(&a).unwrap(), (0, 0));
+
+        // Inverse of step, across Lv1 boundaries too
+        let a = MeshCode::try_from(5339).unwrap();
+        let b = MeshCode::try_from(5235).unwrap();
+        let (d_row, d_col) = a.cell_offset(&b).unwrap();
+        assert_eq!((d_row, d_col), (-1, -4));
+        assert_eq!(a.step(d_row, d_col).unwrap(), b);
+
+        let parent = MeshCode::try_from(533935).unwrap();
+        assert_eq!(
+            MeshCode::try_from(53393599).unwrap().cell_offset(&parent),
+            Err(JismeshError::MismatchedMeshLevels(
+                MeshLevel::Lv3,
+                MeshLevel::Lv2
+            ))
+        );
+    }
+
+    #[test]
+    fn test_approx_equals() {
+        let meshcode = MeshCode::try_from(53393599).unwrap();
+        assert!(meshcode.approx_equals(&meshcode, 0));
+
+        // Adjacent codes across a Lv2 boundary, including diagonals
+        let north = MeshCode::try_from(53394509).unwrap();
+        let north_east = meshcode.step(1, 1).unwrap();
+        assert!(meshcode.approx_equals(&north, 1));
+        assert!(meshcode.approx_equals(&north_east, 1));
+        assert!(!meshcode.approx_equals(&north, 0));
+        assert!(!meshcode.approx_equals(&north_east, 0));
+
+        let far = meshcode.step(2, -1).unwrap();
+        assert!(!meshcode.approx_equals(&far, 1));
+        assert!(far.approx_equals(&meshcode, 2));
+
+        // Different levels are never equal
+        let parent = MeshCode::try_from(533935).unwrap();
+        assert!(!meshcode.approx_equals(&parent, 100));
+    }
+
+    #[test]
+    fn test_walk_to() {
+        let meshcode = MeshCode::try_from(53393599).unwrap();
+        assert_eq!(meshcode.walk_to(&meshcode).unwrap(), vec![meshcode]);
+
+        // A horizontal walk visits exactly the columns in between, across a Lv2 boundary
+        let from = meshcode.step(0, -2).unwrap();
+        let to = meshcode.step(0, 2).unwrap();
+        let path = from.walk_to(&to).unwrap();
+        assert_eq!(path, vec![53393597, 53393598, 53393599, 53393690, 53393691]);
+
+        // The reverse walk is the same chain backwards
+        let mut reverse = to.walk_to(&from).unwrap();
+        reverse.reverse();
+        assert_eq!(reverse, path);
+
+        // A diagonal-ish walk is gap free and ends at the target
+        let to = meshcode.step(3, -7).unwrap();
+        let path = meshcode.walk_to(&to).unwrap();
+        assert_eq!(path.len(), 8);
+        assert_eq!(path.first(), Some(&meshcode));
+        assert_eq!(path.last(), Some(&to));
+        for pair in path.windows(2) {
+            assert!(pair[0].approx_equals(&pair[1], 1));
+            assert_ne!(pair[0], pair[1]);
+        }
+
+        let parent = MeshCode::try_from(533935).unwrap();
+        assert_eq!(
+            meshcode.walk_to(&parent),
+            Err(JismeshError::MismatchedMeshLevels(
+                MeshLevel::Lv3,
+                MeshLevel::Lv2
+            ))
+        );
+    }
+
+    #[test]
+    fn test_square() {
+        let meshcode = MeshCode::try_from(53393599).unwrap();
+        let square = meshcode.square(1).unwrap();
+        assert_eq!(square.len(), 9);
+        assert_eq!(square[0], 53393588);
+        assert_eq!(square[4], meshcode);
+        assert_eq!(square[8], 53394600);
+
+        assert_eq!(meshcode.square(0).unwrap(), vec![meshcode]);
+        assert_eq!(meshcode.square(2).unwrap().len(), 25);
+
+        let meshcode = MeshCode::try_from(5379).unwrap();
+        assert!(meshcode.square(1).is_err());
+
+        // A radius larger than the grid is an error, not an overflow or a huge allocation
+        let meshcode = MeshCode::try_from(53393599).unwrap();
+        assert!(meshcode.square(u32::MAX).is_err());
+    }
+
+    #[test]
+    fn test_square_clamped() {
+        let meshcode = MeshCode::try_from(53393599).unwrap();
+        assert_eq!(meshcode.square_clamped(1), meshcode.square(1).unwrap());
+
+        // 6848 is on the northern edge of the national bbox, so the row north of it is dropped
+        let meshcode = MeshCode::try_from(6848).unwrap();
+        let square = meshcode.square_clamped(1);
+        assert!(square.len() < 9);
+        assert_eq!(square.len(), 6);
+        assert!(square.iter().all(|code| code.is_within_japan()));
+
+        // Off the domain edge, no error is returned
+        let meshcode = MeshCode::try_from(5379).unwrap();
+        assert!(meshcode.square_clamped(1).is_empty());
+
+        // A radius larger than the grid is limited to the grid
+        let meshcode = MeshCode::try_from(5339).unwrap();
+        assert_eq!(
+            meshcode.square_clamped(u32::MAX),
+            meshcode.square_clamped(100)
+        );
+    }
+
+    #[test]
+    fn test_grid_iter() {
+        let sw = MeshCode::try_from(58405438).unwrap().global_index();
+        let ne = MeshCode::try_from(58405449).unwrap().global_index();
+        let iter = GridIter::new(MeshLevel::Lv3, sw, ne);
+        assert_eq!(iter.total(), 4);
+        let codes: Vec<u64> = iter.map(|c| c.into()).collect();
+        assert_eq!(codes, vec![58405438, 58405439, 58405448, 58405449]);
+
+        let iter = GridIter::new(MeshLevel::Lv3, ne, sw);
+        assert_eq!(iter.total(), 0);
+        assert_eq!(iter.count(), 0);
+    }
+
+    #[test]
+    fn test_spatial_key_round_trip() {
+        for level in MeshLevel::iter() {
+            let meshcode = MeshCode::try_from_latlng(35.658581, 139.745433, level).unwrap();
+            let key = meshcode.spatial_key();
+            assert_eq!(MeshCode::from_spatial_key(key), Ok(meshcode));
+        }
+        // The extreme corners of the grid also round trip
+        for (row, col) in [(0, 0), (64000 - 1, 51200 - 1)] {
+            let meshcode = MeshCode::from_global_index(row, col, MeshLevel::Lv6).unwrap();
+            assert_eq!(
+                MeshCode::from_spatial_key(meshcode.spatial_key()),
+                Ok(meshcode)
+            );
+        }
+    }
+
+    #[test]
+    fn test_spatial_key_nearby() {
+        let meshcode = MeshCode::try_from(53393588).unwrap();
+        let key = meshcode.spatial_key();
+        // Keys are tagged with the level, so levels don't interleave
+        assert_eq!(key >> 56, 11);
+
+        let east = meshcode.step(0, 1).unwrap().spatial_key();
+        let north = meshcode.step(1, 0).unwrap().spatial_key();
+        let far = meshcode.step(100, 100).unwrap().spatial_key();
+        assert_eq!(east - key, 1);
+        assert_eq!(north - key, 2);
+        assert!(far - key > 1000);
+    }
+
+    #[test]
+    fn test_from_spatial_key_invalid() {
+        for key in [0, 15 << 56, (11 << 56) | (1 << 40), u64::MAX] {
+            assert_eq!(
+                MeshCode::from_spatial_key(key),
+                Err(JismeshError::InvalidSpatialKey(key))
+            );
+        }
+        // Out of range for Lv1 (only 100 rows)
+        let key = (1 << 56) | (spread_bits(100) << 1);
+        assert_eq!(
+            MeshCode::from_spatial_key(key),
+            Err(JismeshError::InvalidSpatialKey(key))
+        );
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_quadrants() {
+        let lv3 = MeshCode::try_from(53393599).unwrap();
+        let quadrants = lv3.quadrants(MeshLevel::Lv4).unwrap();
+        assert_eq!(quadrants.len(), 4);
+        assert_eq!(quadrants[&Quadrant::SW], 533935991);
+        assert_eq!(quadrants[&Quadrant::SE], 533935992);
+        assert_eq!(quadrants[&Quadrant::NW], 533935993);
+        assert_eq!(quadrants[&Quadrant::NE], 533935994);
+
+        // The quadrants tile the parent
+        let (lat_s, lon_w, lat_n, lon_e) = lv3.bounds().unwrap();
+        let sw = quadrants[&Quadrant::SW].bounds().unwrap();
+        let ne = quadrants[&Quadrant::NE].bounds().unwrap();
+        for (a, b) in [(sw.0, lat_s), (sw.1, lon_w), (ne.2, lat_n), (ne.3, lon_e)] {
+            assert!((a - b).abs() < 1e-12);
+        }
+        for code in quadrants.values() {
+            assert!(lv3.contains(code));
+        }
+
+        // Extended levels that halve their parent
+        let lv2 = MeshCode::try_from(533935).unwrap();
+        let quadrants = lv2.quadrants(MeshLevel::X5).unwrap();
+        assert_eq!(quadrants[&Quadrant::NE], 5339354);
+        let lv1 = MeshCode::try_from(5339).unwrap();
+        assert_eq!(lv1.quadrants(MeshLevel::X40).unwrap()[&Quadrant::SE], 53392);
+
+        assert!(lv3.quadrants(MeshLevel::Lv5).is_err());
+        assert!(lv3.quadrants(MeshLevel::Lv2).is_err());
+        assert!(lv2.quadrants(MeshLevel::Lv3).is_err());
+    }
+
+    #[test]
+    fn test_border_cells() {
+        let lv2 = MeshCode::try_from(533935).unwrap();
+        let border = lv2.border_cells(MeshLevel::Lv3).unwrap();
+        assert_eq!(border.len(), 12 * 12 - 10 * 10);
+        assert_eq!(border.first().unwrap().value, 53392499);
+        assert_eq!(border.last().unwrap().value, 53394600);
+        for code in &border {
+            assert_eq!(code.level, MeshLevel::Lv3);
+            assert!(!lv2.contains(code));
+            assert!(
+                lv2.border_cells(MeshLevel::Lv2)
+                    .unwrap()
+                    .contains(&code.clamp_to_level(MeshLevel::Lv2).unwrap())
+            );
+        }
+
+        // At the same level, the border is the 8 neighbours
+        assert_eq!(lv2.border_cells(MeshLevel::Lv2).unwrap().len(), 8);
+
+        // Cells outside the valid range are dropped
+        let corner = MeshCode::from_global_index(0, 0, MeshLevel::Lv2).unwrap();
+        assert_eq!(corner.border_cells(MeshLevel::Lv3).unwrap().len(), 21);
+
+        assert!(lv2.border_cells(MeshLevel::Lv1).is_err());
+        assert!(lv2.border_cells(MeshLevel::X2_5).is_ok());
+        assert!(lv2.border_cells(MeshLevel::X8).is_err());
+    }
+
+    #[test]
+    fn test_children() {
+        let lv1 = MeshCode::try_from(5339).unwrap();
+        let children = lv1.children(MeshLevel::Lv2).unwrap();
+        assert_eq!(children.len(), 64);
+        assert_eq!(children[0], 533900);
+        assert_eq!(children[1], 533901);
+        assert_eq!(children[63], 533977);
+
+        let lv2 = MeshCode::try_from(533935).unwrap();
+        let children = lv2.children(MeshLevel::Lv3).unwrap();
+        assert_eq!(children.len(), 100);
+        assert_eq!(children[0], 53393500);
+        assert_eq!(children[99], 53393599);
+        assert!(children.iter().all(|child| lv2.contains(child)));
+        assert_eq!(lv2.children(MeshLevel::X5).unwrap().len(), 4);
+        assert_eq!(lv2.children(MeshLevel::Lv6).unwrap().len(), 6400);
+
+        let lv3 = MeshCode::try_from(53393599).unwrap();
+        assert_eq!(
+            lv3.children(MeshLevel::Lv4).unwrap(),
+            vec![533935991, 533935992, 533935993, 533935994]
+        );
+
+        assert_eq!(
+            lv3.children(MeshLevel::Lv3),
+            Err(JismeshError::InvalidMeshLevelForHigherLevel(
+                MeshLevel::Lv3,
+                MeshLevel::Lv3
+            ))
+        );
+        assert_eq!(
+            lv3.children(MeshLevel::Lv2),
+            Err(JismeshError::InvalidMeshLevelForHigherLevel(
+                MeshLevel::Lv2,
+                MeshLevel::Lv3
+            ))
+        );
+        // Lv3 meshes don't nest into X2.5 meshes
+        let x2_5 = MeshCode::try_from(533935446).unwrap();
+        assert_eq!(
+            x2_5.children(MeshLevel::Lv3),
+            Err(JismeshError::UnsupportedMeshLevelConversion(
+                MeshLevel::X2_5,
+                MeshLevel::Lv3
+            ))
+        );
+    }
+
+    #[test]
+    fn test_intersects_iter() {
+        let cases: [(u64, MeshLevel, &[u64]); 11] = [
+            // Coarser levels that the mesh nests into
+            (53393599, MeshLevel::Lv1, &[5339]),
+            (53393599, MeshLevel::X40, &[53392]),
+            (533935885, MeshLevel::X16, &[5339467]),
+            // The same level
+            (53393599, MeshLevel::Lv3, &[53393599]),
+            // Finer levels
+            (
+                5339467,
+                MeshLevel::X8,
+                &[5339466, 5339476, 5339566, 5339576],
+            ),
+            (
+                533935,
+                MeshLevel::X2_5,
+                &[
+                    533935116, 533935126, 533935216, 533935226, 533935136, 533935146, 533935236,
+                    533935246, 533935316, 533935326, 533935416, 533935426, 533935336, 533935346,
+                    533935436, 533935446,
+                ],
+            ),
+            // Levels whose borders don't line up
+            (53393592, MeshLevel::X2_5, &[533935336, 533935346]),
+            (
+                5339467,
+                MeshLevel::Lv2,
+                &[533934, 533935, 533936, 533944, 533945, 533946],
+            ),
+            (533935, MeshLevel::X16, &[5339267, 5339467]),
+            // Meshes that only touch the south-west corner are not included
+            (533900, MeshLevel::X16, &[5339007]),
+            (53390000, MeshLevel::X16, &[5339007]),
+        ];
+        for (value, to_level, expected) in cases {
+            let meshcode = MeshCode::try_from(value).unwrap();
+            let result: Vec<u64> = meshcode
+                .intersects_iter(to_level)
+                .unwrap()
+                .map(u64::from)
+                .collect();
+            assert_eq!(result, expected, "Failed for {} to {}", value, to_level);
+        }
+
+        // An X16 mesh is 8 x 8 X2 meshes, from 533934285 in the south-west to 533946625
+        // in the north-east
+        let x2: Vec<MeshCode> = MeshCode::try_from(5339467)
+            .unwrap()
+            .intersects_iter(MeshLevel::X2)
+            .unwrap()
+            .collect();
+        assert_eq!(x2.len(), 64);
+        assert_eq!(x2.first().unwrap(), &533934285);
+        assert_eq!(x2.last().unwrap(), &533946625);
+
+        // Lv1 to Lv6 is generated lazily
+        let lv1 = MeshCode::try_from(5339).unwrap();
+        let mut iter = lv1.intersects_iter(MeshLevel::Lv6).unwrap();
+        assert_eq!(iter.next().unwrap(), 53390000111);
+        assert_eq!(iter.next().unwrap(), 53390000112);
+        assert_eq!(
+            lv1.intersects_iter(MeshLevel::Lv6).unwrap().count(),
+            640 * 640
+        );
+    }
+
+    #[test]
+    fn test_for_each_child() {
+        let lv2 = MeshCode::try_from(533935).unwrap();
+        let mut children = Vec::new();
+        let result = lv2.for_each_child(MeshLevel::Lv3, |child| {
+            children.push(child);
+            ControlFlow::Continue(())
+        });
+        assert_eq!(result, Ok(ControlFlow::Continue(())));
+        assert_eq!(children.len(), 100);
+        assert_eq!(children[0].value, 53393500);
+        assert_eq!(children[99].value, 53393599);
+
+        // Breaking after N children stops the generation, even for Lv1 -> Lv6
+        let lv1 = MeshCode::try_from(5339).unwrap();
+        let mut count = 0;
+        let result = lv1.for_each_child(MeshLevel::Lv6, |child| {
+            assert_eq!(child.level, MeshLevel::Lv6);
+            count += 1;
+            if count == 10 {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(())
+            }
+        });
+        assert_eq!(result, Ok(ControlFlow::Break(())));
+        assert_eq!(count, 10);
+
+        assert!(
+            lv2.for_each_child(MeshLevel::Lv1, |_| ControlFlow::Continue(()))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_code_value_range() {
+        let lv2 = MeshCode::try_from(533935).unwrap();
+        let (min, max) = lv2.code_value_range(MeshLevel::Lv4).unwrap();
+        assert_eq!((min, max), (533935001, 533935994));
+        let children: Vec<MeshCode> = lv2.descendants(MeshLevel::Lv4).unwrap().collect();
+        assert_eq!(children.len(), 400);
+        for child in children {
+            assert!((min..=max).contains(&child.value));
+            assert!(lv2.contains(&child));
+        }
+
+        // Extended levels nest too, even though the digits don't share a prefix
+        let x40 = MeshCode::try_from(53392).unwrap();
+        assert_eq!(x40.level, MeshLevel::X40);
+        let (min, max) = x40.code_value_range(MeshLevel::Lv2).unwrap();
+        for child in x40.descendants(MeshLevel::Lv2).unwrap() {
+            assert!((min..=max).contains(&child.value));
+        }
+
+        assert_eq!(
+            lv2.code_value_range(MeshLevel::Lv2).unwrap(),
+            (533935, 533935)
+        );
+    }
+
+    #[test]
+    fn test_code_value_range_errors() {
+        let lv2 = MeshCode::try_from(533935).unwrap();
+        assert_eq!(
+            lv2.code_value_range(MeshLevel::Lv1),
+            Err(JismeshError::InvalidMeshLevelForHigherLevel(
+                MeshLevel::Lv1,
+                MeshLevel::Lv2
+            ))
+        );
+        let x8 = MeshCode::try_from(5339476).unwrap();
+        assert_eq!(
+            x8.code_value_range(MeshLevel::X5),
+            Err(JismeshError::UnsupportedMeshLevelConversion(
+                MeshLevel::X8,
+                MeshLevel::X5
+            ))
+        );
+    }
+
+    #[test]
+    fn test_from_global_index_out_of_bounds() {
+        assert!(MeshCode::from_global_index(100, 0, MeshLevel::Lv1).is_err());
+        assert!(MeshCode::from_global_index(0, 80, MeshLevel::Lv1).is_err());
+        assert!(MeshCode::from_global_index(0, 80 * 80, MeshLevel::Lv3).is_err());
+    }
+}
@@ -1,40 +1,94 @@
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)] // see `utils/mod.rs`
+use super::FloatExt;
+use super::grid::cells_per_lv1;
 use super::{JismeshError, Result};
-use std::fmt;
+#[cfg(not(feature = "std"))]
+use super::{String, Vec, format};
+use core::fmt;
+use strum::IntoEnumIterator;
 use strum_macros::{EnumIter, EnumString};
 
 /// 地域メッシュコードの次数
-#[derive(Debug, Clone, Copy, Eq, EnumIter, EnumString, PartialEq, PartialOrd, Ord, Hash)]
+///
+/// 大小比較はメッシュの大きさの順で、粗い次数ほど小さくなります（Lv1 < X40 < ... < Lv6）。
+///
+/// `FromStr` は "Lv4" のような名前のほか、 `to_fraction_jp` の名称（"1/2地域メッシュ" など）と、
+/// 4次〜6次の "2分の1地域メッシュ" のような表記も受け付けます。
+#[derive(Debug, Clone, Copy, Eq, EnumIter, EnumString, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MeshLevel {
     /// 1次(80km四方) 4桁
+    #[strum(serialize = "Lv1", serialize = "第1次地域区画")]
     Lv1 = 1,
     /// 40倍(40km四方)
+    #[strum(serialize = "X40", serialize = "40倍地域メッシュ")]
     X40 = 40000,
     /// 20倍(20km四方)
+    #[strum(serialize = "X20", serialize = "20倍地域メッシュ")]
     X20 = 20000,
     /// 16倍(16km四方)
+    ///
+    /// 1次メッシュを 5×5 に分割します。区画番号（5・6桁目）は 0, 2, 4, 6, 8 の偶数のみを使い、
+    /// 末尾は 7 です（例: 5339467）。
+    #[strum(serialize = "X16", serialize = "16倍地域メッシュ")]
     X16 = 16000,
     /// 2次(10km四方) 6桁
+    #[strum(serialize = "Lv2", serialize = "第2次地域区画")]
     Lv2 = 2,
     /// 8倍(8km四方)
+    #[strum(serialize = "X8", serialize = "8倍地域メッシュ")]
     X8 = 8000,
     /// 5倍(5km四方)
+    #[strum(serialize = "X5", serialize = "5倍地域メッシュ")]
     X5 = 5000,
     /// 4倍(4km四方)
+    #[strum(serialize = "X4", serialize = "4倍地域メッシュ")]
     X4 = 4000,
     /// 2.5倍(2.5km四方)
+    #[strum(serialize = "X2_5", serialize = "2.5倍地域メッシュ")]
     X2_5 = 2500,
     /// 2倍(2km四方)
+    ///
+    /// 2次メッシュを 5×5 に分割します。区画番号（7・8桁目）は 0, 2, 4, 6, 8 の偶数のみを使い、
+    /// 末尾は 5 です（例: 533935885）。
+    #[strum(serialize = "X2", serialize = "2倍地域メッシュ")]
     X2 = 2000,
     /// 3次(1km四方) 8桁
+    #[strum(serialize = "Lv3", serialize = "基準地域メッシュ")]
     Lv3 = 3,
     /// 4次(500m四方)
+    #[strum(
+        serialize = "Lv4",
+        serialize = "1/2地域メッシュ",
+        serialize = "2分の1地域メッシュ"
+    )]
     Lv4 = 4,
     /// 5次(250m四方)
+    #[strum(
+        serialize = "Lv5",
+        serialize = "1/4地域メッシュ",
+        serialize = "4分の1地域メッシュ"
+    )]
     Lv5 = 5,
     /// 6次(125m四方)
+    #[strum(
+        serialize = "Lv6",
+        serialize = "1/8地域メッシュ",
+        serialize = "8分の1地域メッシュ"
+    )]
     Lv6 = 6,
 }
 
+/// メッシュサイズを表示する際の単位
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SizeUnit {
+    /// キロメートル (km)
+    Kilometer,
+    /// メートル (m)
+    Meter,
+}
+
 impl MeshLevel {
     /// メッシュコードの日本語名を取得する
     pub fn to_string_jp(&self) -> &str {
@@ -55,6 +109,27 @@ impl MeshLevel {
             MeshLevel::Lv6 => "6次",
         }
     }
+    /// 統計資料などで使われる地域メッシュの名称を取得する
+    /// 例: Lv3 は "基準地域メッシュ" 、 Lv4 は "1/2地域メッシュ" 、 Lv6 は "1/8地域メッシュ"
+    pub fn to_fraction_jp(&self) -> &str {
+        match self {
+            MeshLevel::Lv1 => "第1次地域区画",
+            MeshLevel::X40 => "40倍地域メッシュ",
+            MeshLevel::X20 => "20倍地域メッシュ",
+            MeshLevel::X16 => "16倍地域メッシュ",
+            MeshLevel::Lv2 => "第2次地域区画",
+            MeshLevel::X8 => "8倍地域メッシュ",
+            MeshLevel::X5 => "5倍地域メッシュ",
+            MeshLevel::X4 => "4倍地域メッシュ",
+            MeshLevel::X2_5 => "2.5倍地域メッシュ",
+            MeshLevel::X2 => "2倍地域メッシュ",
+            MeshLevel::Lv3 => "基準地域メッシュ",
+            MeshLevel::Lv4 => "1/2地域メッシュ",
+            MeshLevel::Lv5 => "1/4地域メッシュ",
+            MeshLevel::Lv6 => "1/8地域メッシュ",
+        }
+    }
+
     /// メッシュコードのおおよそのサイズを取得する（日本語）
     /// 例: "80km四方"
     pub fn to_size_jp(&self) -> &str {
@@ -75,6 +150,171 @@ impl MeshLevel {
             MeshLevel::Lv6 => "125m四方",
         }
     }
+
+    /// 拡張統合地域メッシュも含めた階層で、直接の親となる次数を取得する
+    ///
+    /// メッシュコードは親のメッシュコードに桁を付け足した形になっています。
+    /// * Lv1 → X40 → X20
+    /// * Lv1 → X16
+    /// * Lv1 → X8 → X4
+    /// * Lv1 → Lv2 → X5 → X2_5
+    /// * Lv1 → Lv2 → X2
+    /// * Lv1 → Lv2 → Lv3 → Lv4 → Lv5 → Lv6
+    ///
+    /// Lv1 は最上位のため None を返します。
+    pub fn direct_parent(&self) -> Option<MeshLevel> {
+        match self {
+            MeshLevel::Lv1 => None,
+            MeshLevel::X40 => Some(MeshLevel::Lv1),
+            MeshLevel::X20 => Some(MeshLevel::X40),
+            MeshLevel::X16 => Some(MeshLevel::Lv1),
+            MeshLevel::Lv2 => Some(MeshLevel::Lv1),
+            MeshLevel::X8 => Some(MeshLevel::Lv1),
+            MeshLevel::X5 => Some(MeshLevel::Lv2),
+            MeshLevel::X4 => Some(MeshLevel::X8),
+            MeshLevel::X2_5 => Some(MeshLevel::X5),
+            MeshLevel::X2 => Some(MeshLevel::Lv2),
+            MeshLevel::Lv3 => Some(MeshLevel::Lv2),
+            MeshLevel::Lv4 => Some(MeshLevel::Lv3),
+            MeshLevel::Lv5 => Some(MeshLevel::Lv4),
+            MeshLevel::Lv6 => Some(MeshLevel::Lv5),
+        }
+    }
+
+    /// 全ての次数を、メッシュの大きさの順（粗い順）に取得する。
+    /// 最初は Lv1 (80km)、最後は Lv6 (125m) です。
+    pub fn iter_by_size() -> impl Iterator<Item = MeshLevel> {
+        let mut levels: Vec<MeshLevel> = MeshLevel::iter().collect();
+        levels.sort();
+        levels.into_iter()
+    }
+
+    /// メッシュのおおよその一辺の長さ（km）を取得する
+    /// 例: Lv1 は 80.0
+    pub fn to_size_km(&self) -> f64 {
+        match self {
+            MeshLevel::Lv1 => 80.0,
+            MeshLevel::X40 => 40.0,
+            MeshLevel::X20 => 20.0,
+            MeshLevel::X16 => 16.0,
+            MeshLevel::Lv2 => 10.0,
+            MeshLevel::X8 => 8.0,
+            MeshLevel::X5 => 5.0,
+            MeshLevel::X4 => 4.0,
+            MeshLevel::X2_5 => 2.5,
+            MeshLevel::X2 => 2.0,
+            MeshLevel::Lv3 => 1.0,
+            MeshLevel::Lv4 => 0.5,
+            MeshLevel::Lv5 => 0.25,
+            MeshLevel::Lv6 => 0.125,
+        }
+    }
+
+    /// メッシュの大きさ（緯度方向, 経度方向）を度単位で取得する
+    ///
+    /// ```
+    /// use jismesh::MeshLevel;
+    ///
+    /// let (lat, lon) = MeshLevel::Lv1.unit_size();
+    /// assert_eq!(lat, 2.0 / 3.0); // 0.6666...
+    /// assert_eq!(lon, 1.0);
+    /// ```
+    pub fn unit_size(&self) -> (f64, f64) {
+        super::unit_lat_lon(*self)
+    }
+
+    /// メッシュのおおよその一辺の長さを、指定された単位で文字列にする
+    /// 例: Lv1 は "80 km" または "80000 m"
+    pub fn format_size(&self, unit: SizeUnit) -> String {
+        match unit {
+            SizeUnit::Kilometer => format!("{} km", self.to_size_km()),
+            SizeUnit::Meter => format!("{} m", self.to_size_km() * 1000.0),
+        }
+    }
+
+    /// 一辺の長さ（km）から次数を取得する
+    /// 例: 1.0 は Lv3 、 0.5 は Lv4 、 8.0 は X8
+    /// 該当する次数がない場合は None を返します。
+    pub fn from_km(km: f64) -> Option<MeshLevel> {
+        MeshLevel::iter().find(|level| (level.to_size_km() - km).abs() < 1e-9)
+    }
+
+    /// 一辺の長さの文字列から次数を取得する。 `from_km` の文字列版です。
+    /// 例: "1km" は Lv3 、 "125m" は Lv6 、 "2.5km" は X2_5
+    ///
+    /// 単位は km または m で、数値と単位の間の空白や、末尾の「四方」は無視します。
+    /// そのため `to_size_jp` や `format_size` の結果も読み込めます。
+    /// 読み込めない場合や、該当する次数がない場合は None を返します。
+    pub fn from_size_str(size: &str) -> Option<MeshLevel> {
+        let size = size.trim();
+        let size = size.strip_suffix("四方").unwrap_or(size).trim_end();
+        let km = if let Some(value) = size.strip_suffix("km") {
+            value.trim_end().parse::<f64>().ok()?
+        } else if let Some(value) = size.strip_suffix('m') {
+            value.trim_end().parse::<f64>().ok()? / 1000.0
+        } else {
+            return None;
+        };
+        MeshLevel::from_km(km)
+    }
+
+    /// 面積（m²）に最も近い大きさの次数を取得する。
+    /// 例: 1,000,000 (100ha) は Lv3 、 15,625 は Lv6
+    ///
+    /// 近さは面積の比で比べます（10,000 m² に対しては、 2倍の面積も半分の面積も同じ近さです）。
+    /// 0 以下の面積や NaN の場合は、最も細かい Lv6 を返します。
+    pub fn closest_to_area(area_m2: f64) -> MeshLevel {
+        if area_m2.is_nan() || area_m2 <= 0.0 {
+            return MeshLevel::Lv6;
+        }
+        MeshLevel::iter()
+            .min_by(|a, b| {
+                let ratio = |level: &MeshLevel| {
+                    let size_m = level.to_size_km() * 1000.0;
+                    (size_m * size_m / area_m2).ln().abs()
+                };
+                ratio(a).total_cmp(&ratio(b))
+            })
+            // MeshLevel always has variants
+            .unwrap()
+    }
+
+    /// この次数のメッシュ 1 つに含まれる、より細かい次数のメッシュの数を（行数, 列数）で取得する。
+    /// 例: Lv1 → Lv3 は (80, 80) 、 Lv2 → X5 は (2, 2) 、同じ次数は (1, 1)
+    ///
+    /// 子メッシュを列挙せずに数が分かるので、バッファの確保などに使えます。
+    /// 指定次数の方が粗い場合や、境界がそろわない組み合わせ（X20 → X16 など）の場合はエラーを返します。
+    pub fn subdivisions_into(&self, finer: MeshLevel) -> Result<(u32, u32)> {
+        if finer < *self {
+            return Err(JismeshError::InvalidMeshLevelForHigherLevel(finer, *self));
+        }
+        let (cells, finer_cells) = (cells_per_lv1(*self), cells_per_lv1(finer));
+        if !finer_cells.is_multiple_of(cells) {
+            return Err(JismeshError::UnsupportedMeshLevelConversion(*self, finer));
+        }
+        let n = (finer_cells / cells) as u32;
+        Ok((n, n))
+    }
+
+    /// この次数のメッシュ 1 つに含まれる、より細かい次数のメッシュの数を取得する。
+    /// `subdivisions_into` の行数と列数の積です。例: Lv1 → Lv3 は 6,400
+    pub fn count_into(&self, finer: MeshLevel) -> Result<u64> {
+        let (rows, cols) = self.subdivisions_into(finer)?;
+        Ok(rows as u64 * cols as u64)
+    }
+}
+
+impl Ord for MeshLevel {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        // Coarser levels come first
+        other.to_size_km().total_cmp(&self.to_size_km())
+    }
+}
+
+impl PartialOrd for MeshLevel {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
 }
 
 impl fmt::Display for MeshLevel {
@@ -110,7 +350,8 @@ impl TryFrom<usize> for MeshLevel {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use strum::IntoEnumIterator;
+    #[cfg(not(feature = "std"))]
+    use alloc::{string::ToString, vec};
 
     #[test]
     fn tesh_meshlevel_sort() {
@@ -136,6 +377,56 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_meshlevel_sort_extended() {
+        let mut levels = vec![
+            MeshLevel::X2,
+            MeshLevel::Lv3,
+            MeshLevel::X40,
+            MeshLevel::Lv2,
+            MeshLevel::X5,
+        ];
+        levels.sort();
+        assert_eq!(
+            levels,
+            vec![
+                MeshLevel::X40,
+                MeshLevel::Lv2,
+                MeshLevel::X5,
+                MeshLevel::X2,
+                MeshLevel::Lv3
+            ]
+        );
+        assert!(MeshLevel::X16 < MeshLevel::Lv2);
+        assert!(MeshLevel::X2_5 > MeshLevel::X4);
+    }
+
+    #[test]
+    fn test_meshlevel_closest_to_area() {
+        assert_eq!(MeshLevel::closest_to_area(1_000_000.0), MeshLevel::Lv3);
+        assert_eq!(MeshLevel::closest_to_area(15_625.0), MeshLevel::Lv6);
+        assert_eq!(MeshLevel::closest_to_area(6_400_000_000.0), MeshLevel::Lv1);
+        // Roughly 1 ha is closest to Lv6 (1.5625 ha)
+        assert_eq!(MeshLevel::closest_to_area(10_000.0), MeshLevel::Lv6);
+        // 30 km² is between X5 (25 km²) and X8 (64 km²)
+        assert_eq!(MeshLevel::closest_to_area(30_000_000.0), MeshLevel::X5);
+        assert_eq!(MeshLevel::closest_to_area(1e12), MeshLevel::Lv1);
+        assert_eq!(MeshLevel::closest_to_area(0.0), MeshLevel::Lv6);
+        assert_eq!(MeshLevel::closest_to_area(-1.0), MeshLevel::Lv6);
+        assert_eq!(MeshLevel::closest_to_area(f64::NAN), MeshLevel::Lv6);
+    }
+
+    #[test]
+    fn test_meshlevel_iter_by_size() {
+        let levels: Vec<MeshLevel> = MeshLevel::iter_by_size().collect();
+        assert_eq!(levels.len(), 14);
+        assert_eq!(levels.first(), Some(&MeshLevel::Lv1));
+        assert_eq!(levels.last(), Some(&MeshLevel::Lv6));
+        for pair in levels.windows(2) {
+            assert!(pair[0].to_size_km() > pair[1].to_size_km());
+        }
+    }
+
     #[test]
     fn test_meshlevel_conversion() {
         assert_eq!(MeshLevel::try_from(1).unwrap(), MeshLevel::Lv1);
@@ -181,4 +472,186 @@ mod tests {
         assert_eq!(level.to_string_jp(), "40倍");
         assert_eq!(level.to_size_jp(), "40km四方");
     }
+
+    #[test]
+    fn test_meshlevel_from_km() {
+        assert_eq!(MeshLevel::from_km(80.0), Some(MeshLevel::Lv1));
+        assert_eq!(MeshLevel::from_km(40.0), Some(MeshLevel::X40));
+        assert_eq!(MeshLevel::from_km(8.0), Some(MeshLevel::X8));
+        assert_eq!(MeshLevel::from_km(2.5), Some(MeshLevel::X2_5));
+        assert_eq!(MeshLevel::from_km(1.0), Some(MeshLevel::Lv3));
+        assert_eq!(MeshLevel::from_km(0.5), Some(MeshLevel::Lv4));
+        assert_eq!(MeshLevel::from_km(0.125), Some(MeshLevel::Lv6));
+        assert_eq!(MeshLevel::from_km(3.0), None);
+
+        for level in MeshLevel::iter() {
+            assert_eq!(MeshLevel::from_km(level.to_size_km()), Some(level));
+        }
+    }
+
+    #[test]
+    fn test_meshlevel_from_size_str() {
+        assert_eq!(MeshLevel::from_size_str("80km"), Some(MeshLevel::Lv1));
+        assert_eq!(MeshLevel::from_size_str("1km"), Some(MeshLevel::Lv3));
+        assert_eq!(MeshLevel::from_size_str("2.5km"), Some(MeshLevel::X2_5));
+        assert_eq!(MeshLevel::from_size_str("125m"), Some(MeshLevel::Lv6));
+        assert_eq!(MeshLevel::from_size_str(" 500 m "), Some(MeshLevel::Lv4));
+        assert_eq!(MeshLevel::from_size_str("1000m"), Some(MeshLevel::Lv3));
+
+        // Every advertised size maps back to its level
+        for level in MeshLevel::iter() {
+            assert_eq!(MeshLevel::from_size_str(level.to_size_jp()), Some(level));
+            for unit in [SizeUnit::Kilometer, SizeUnit::Meter] {
+                assert_eq!(
+                    MeshLevel::from_size_str(&level.format_size(unit)),
+                    Some(level)
+                );
+            }
+        }
+
+        assert_eq!(MeshLevel::from_size_str("3km"), None);
+        assert_eq!(MeshLevel::from_size_str("1"), None);
+        assert_eq!(MeshLevel::from_size_str("km"), None);
+        assert_eq!(MeshLevel::from_size_str("1mi"), None);
+        assert_eq!(MeshLevel::from_size_str(""), None);
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(MeshLevel::Lv1.format_size(SizeUnit::Kilometer), "80 km");
+        assert_eq!(MeshLevel::Lv1.format_size(SizeUnit::Meter), "80000 m");
+        assert_eq!(MeshLevel::X2_5.format_size(SizeUnit::Kilometer), "2.5 km");
+        assert_eq!(MeshLevel::X2_5.format_size(SizeUnit::Meter), "2500 m");
+        assert_eq!(MeshLevel::Lv6.format_size(SizeUnit::Kilometer), "0.125 km");
+        assert_eq!(MeshLevel::Lv6.format_size(SizeUnit::Meter), "125 m");
+    }
+
+    #[test]
+    fn test_direct_parent() {
+        let cases = vec![
+            (MeshLevel::Lv1, None),
+            (MeshLevel::X40, Some(MeshLevel::Lv1)),
+            (MeshLevel::X20, Some(MeshLevel::X40)),
+            (MeshLevel::X16, Some(MeshLevel::Lv1)),
+            (MeshLevel::Lv2, Some(MeshLevel::Lv1)),
+            (MeshLevel::X8, Some(MeshLevel::Lv1)),
+            (MeshLevel::X5, Some(MeshLevel::Lv2)),
+            (MeshLevel::X4, Some(MeshLevel::X8)),
+            (MeshLevel::X2_5, Some(MeshLevel::X5)),
+            (MeshLevel::X2, Some(MeshLevel::Lv2)),
+            (MeshLevel::Lv3, Some(MeshLevel::Lv2)),
+            (MeshLevel::Lv4, Some(MeshLevel::Lv3)),
+            (MeshLevel::Lv5, Some(MeshLevel::Lv4)),
+            (MeshLevel::Lv6, Some(MeshLevel::Lv5)),
+        ];
+        for (level, expected) in cases {
+            assert_eq!(level.direct_parent(), expected, "Failed for {}", level);
+        }
+
+        // Every level reaches Lv1, and each parent is strictly larger
+        for level in MeshLevel::iter() {
+            let mut current = level;
+            while let Some(parent) = current.direct_parent() {
+                assert!(parent.to_size_km() > current.to_size_km());
+                current = parent;
+            }
+            assert_eq!(current, MeshLevel::Lv1);
+        }
+    }
+
+    #[test]
+    fn test_unit_size() {
+        assert_eq!(MeshLevel::Lv1.unit_size(), (2.0 / 3.0, 1.0));
+        assert_eq!(MeshLevel::Lv3.unit_size(), (2.0 / 3.0 / 80.0, 1.0 / 80.0));
+        // Every level is 1.5 times wider in longitude than in latitude
+        for level in MeshLevel::iter() {
+            let (lat, lon) = level.unit_size();
+            assert!((lon / lat - 1.5).abs() < 1e-9, "{:?}", level);
+        }
+    }
+
+    #[test]
+    fn test_subdivisions_into() {
+        let cases = vec![
+            (MeshLevel::Lv1, MeshLevel::Lv1, (1, 1)),
+            (MeshLevel::Lv1, MeshLevel::Lv2, (8, 8)),
+            (MeshLevel::Lv1, MeshLevel::Lv3, (80, 80)),
+            (MeshLevel::Lv1, MeshLevel::Lv6, (640, 640)),
+            (MeshLevel::Lv1, MeshLevel::X16, (5, 5)),
+            (MeshLevel::Lv2, MeshLevel::X5, (2, 2)),
+            (MeshLevel::Lv2, MeshLevel::X2, (5, 5)),
+            (MeshLevel::X8, MeshLevel::X4, (2, 2)),
+            (MeshLevel::X16, MeshLevel::X8, (2, 2)),
+            (MeshLevel::X5, MeshLevel::X2_5, (2, 2)),
+            (MeshLevel::X2_5, MeshLevel::Lv6, (20, 20)),
+            (MeshLevel::Lv3, MeshLevel::Lv4, (2, 2)),
+        ];
+        for (level, finer, expected) in cases {
+            assert_eq!(
+                level.subdivisions_into(finer),
+                Ok(expected),
+                "{:?} -> {:?}",
+                level,
+                finer
+            );
+            assert_eq!(
+                level.count_into(finer),
+                Ok(expected.0 as u64 * expected.1 as u64)
+            );
+        }
+        assert_eq!(MeshLevel::Lv1.count_into(MeshLevel::Lv3), Ok(6400));
+    }
+
+    #[test]
+    fn test_subdivisions_into_errors() {
+        // Coarser levels
+        assert_eq!(
+            MeshLevel::Lv3.subdivisions_into(MeshLevel::Lv2),
+            Err(JismeshError::InvalidMeshLevelForHigherLevel(
+                MeshLevel::Lv2,
+                MeshLevel::Lv3
+            ))
+        );
+        // Boundaries don't line up
+        assert_eq!(
+            MeshLevel::X20.subdivisions_into(MeshLevel::X16),
+            Err(JismeshError::UnsupportedMeshLevelConversion(
+                MeshLevel::X20,
+                MeshLevel::X16
+            ))
+        );
+        assert!(MeshLevel::X16.count_into(MeshLevel::X5).is_err());
+        assert!(MeshLevel::X2_5.count_into(MeshLevel::X2).is_err());
+    }
+
+    #[test]
+    fn test_to_fraction_jp() {
+        assert_eq!(MeshLevel::Lv3.to_fraction_jp(), "基準地域メッシュ");
+        assert_eq!(MeshLevel::Lv4.to_fraction_jp(), "1/2地域メッシュ");
+        assert_eq!(MeshLevel::Lv5.to_fraction_jp(), "1/4地域メッシュ");
+        assert_eq!(MeshLevel::Lv6.to_fraction_jp(), "1/8地域メッシュ");
+
+        // Every name can be parsed back
+        for level in MeshLevel::iter() {
+            assert_eq!(level.to_fraction_jp().parse::<MeshLevel>(), Ok(level));
+            // The variant names still work
+            assert_eq!(level.to_string().parse::<MeshLevel>(), Ok(level));
+        }
+    }
+
+    #[test]
+    fn test_fraction_jp_aliases() {
+        let cases = vec![
+            ("1/2地域メッシュ", MeshLevel::Lv4),
+            ("2分の1地域メッシュ", MeshLevel::Lv4),
+            ("1/4地域メッシュ", MeshLevel::Lv5),
+            ("4分の1地域メッシュ", MeshLevel::Lv5),
+            ("1/8地域メッシュ", MeshLevel::Lv6),
+            ("8分の1地域メッシュ", MeshLevel::Lv6),
+        ];
+        for (name, expected) in cases {
+            assert_eq!(name.parse::<MeshLevel>(), Ok(expected), "{}", name);
+        }
+        assert!("1/16地域メッシュ".parse::<MeshLevel>().is_err());
+    }
 }
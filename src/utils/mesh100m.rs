@@ -0,0 +1,125 @@
+use super::*;
+use core::fmt;
+
+/// 国勢調査などの地域メッシュ統計で使われる 100m メッシュ（3次メッシュの 1/10 細分区画）
+///
+/// 3次メッシュ（8桁）を緯度・経度方向にそれぞれ 10 等分し、緯度方向の番号（0〜9）と
+/// 経度方向の番号（0〜9）を続けた 10桁のコードです（例: 5339359906）。
+///
+/// 10桁のコードは 5次メッシュ（例: 5339359912）と桁数が同じで、末尾 2桁が 1〜4 の場合は
+/// どちらとも解釈できます。そのため `MeshLevel` には含めず、 `to_meshlevel` や
+/// `MeshCode::try_from` では判定しません。100m メッシュであることが分かっているコードは
+/// `Mesh100m::try_from` で変換してください。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Mesh100m {
+    pub(crate) value: u64,
+}
+
+impl Mesh100m {
+    /// 緯度経度から 100m メッシュコードを生成する。
+    pub fn try_from_latlng(lat: f64, lon: f64) -> Result<Self> {
+        let parent = MeshCode::try_from_latlng(lat, lon, MeshLevel::Lv3)?;
+        let rem_lat_lv3 = lat % UNIT_LAT_LV1 % UNIT_LAT_LV2 % UNIT_LAT_LV3;
+        let rem_lon_lv3 = lon % 100.0 % UNIT_LON_LV1 % UNIT_LON_LV2 % UNIT_LON_LV3;
+        let i = ((rem_lat_lv3 / (UNIT_LAT_LV3 / 10.0)) as u64).min(9);
+        let j = ((rem_lon_lv3 / (UNIT_LON_LV3 / 10.0)) as u64).min(9);
+        Ok(Mesh100m {
+            value: parent.value * 100 + i * 10 + j,
+        })
+    }
+
+    /// この 100m メッシュを含む 3次メッシュコードを取得する。
+    pub fn parent(&self) -> MeshCode {
+        MeshCode {
+            value: self.value / 100,
+            level: MeshLevel::Lv3,
+        }
+    }
+
+    /// 100m メッシュから緯度経度の座標を取得する。 `MeshCode::point` と同じく、
+    /// lat/lon_multiplier で位置を調整できます。返却値は (緯度, 経度) です。
+    pub fn point(&self, lat_multiplier: f64, lon_multiplier: f64) -> Result<(f64, f64)> {
+        let i = (self.value / 10 % 10) as f64;
+        let j = (self.value % 10) as f64;
+        self.parent()
+            .point((i + lat_multiplier) / 10.0, (j + lon_multiplier) / 10.0)
+    }
+}
+
+impl TryFrom<u64> for Mesh100m {
+    type Error = JismeshError;
+
+    fn try_from(value: u64) -> Result<Self> {
+        if !(1_000_000_000..10_000_000_000).contains(&value)
+            || MeshCode::new(value / 100, MeshLevel::Lv3).is_err()
+        {
+            return Err(JismeshError::InvalidMeshCode(value.to_string()));
+        }
+        Ok(Mesh100m { value })
+    }
+}
+
+impl From<Mesh100m> for u64 {
+    fn from(mesh: Mesh100m) -> Self {
+        mesh.value
+    }
+}
+
+impl fmt::Display for Mesh100m {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_mesh100m_from_latlng() {
+        // Tokyo Tower is in the south row, 7th column of 53393599
+        let mesh = Mesh100m::try_from_latlng(35.658581, 139.745433).unwrap();
+        assert_eq!(u64::from(mesh), 5339359906);
+        assert_eq!(mesh.to_string(), "5339359906");
+        assert_eq!(mesh.parent(), MeshCode::try_from(53393599).unwrap());
+
+        assert_eq!(
+            Mesh100m::try_from_latlng(80.0, 139.745433),
+            Err(JismeshError::LatitudeOutOfBounds {
+                value: 80.0,
+                index: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn test_mesh100m_round_trip() {
+        let parent = MeshCode::try_from(53393599).unwrap();
+        for i in 0..10 {
+            for j in 0..10 {
+                let value = parent.value * 100 + i * 10 + j;
+                let mesh = Mesh100m::try_from(value).unwrap();
+                let (lat, lon) = mesh.point(0.5, 0.5).unwrap();
+                assert_eq!(Mesh100m::try_from_latlng(lat, lon).unwrap(), mesh);
+            }
+        }
+
+        // The mesh is 1/10 of the Lv3 mesh
+        let mesh = Mesh100m::try_from(5339359906).unwrap();
+        let (lat_s, lon_w) = mesh.point(0.0, 0.0).unwrap();
+        let (lat_n, lon_e) = mesh.point(1.0, 1.0).unwrap();
+        assert_relative_eq!(lat_n - lat_s, 30.0 / 3600.0 / 10.0, epsilon = 1e-12);
+        assert_relative_eq!(lon_e - lon_w, 45.0 / 3600.0 / 10.0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_mesh100m_invalid() {
+        for value in [53393599, 533935990612, 999999999] {
+            assert_eq!(
+                Mesh100m::try_from(value),
+                Err(JismeshError::InvalidMeshCode(value.to_string()))
+            );
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use super::*;
 use crate::utils::error::JismeshError;
-use std::{fmt, str::FromStr};
+use core::{fmt, str::FromStr};
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
 
 /// 地域メッシュコードを表す構造体
 ///
@@ -12,7 +14,62 @@ pub struct MeshCode {
     pub level: MeshLevel,
 }
 
+/// メッシュ上の代表的な位置（`MeshCode::corner` で使います）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter)]
+pub enum Corner {
+    /// 南西端
+    SW,
+    /// 南東端
+    SE,
+    /// 北西端
+    NW,
+    /// 北東端
+    NE,
+    /// 中心
+    Center,
+}
+
+/// 緯度経度がメッシュの境界線上にある場合に、どちらのメッシュに含めるかの規則
+/// （`to_meshcode_with_boundary` で使います）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, EnumIter)]
+pub enum BoundaryRule {
+    /// 南端・西端の境界線をメッシュに含める（`to_meshcode` と同じ）。
+    /// 境界線上の点は、北側・東側のメッシュになります。
+    #[default]
+    LowerInclusive,
+    /// 北端・東端の境界線をメッシュに含める。
+    /// 境界線上の点は、南側・西側のメッシュになります。
+    UpperInclusive,
+}
+
+impl Corner {
+    /// `MeshCode::point` に渡す (lat_multiplier, lon_multiplier)
+    fn multipliers(&self) -> (f64, f64) {
+        match self {
+            Corner::SW => (0.0, 0.0),
+            Corner::SE => (0.0, 1.0),
+            Corner::NW => (1.0, 0.0),
+            Corner::NE => (1.0, 1.0),
+            Corner::Center => (0.5, 0.5),
+        }
+    }
+}
+
 impl MeshCode {
+    /// 値と次数を指定してメッシュコードを生成する。
+    /// 値から判定した次数が指定した次数と一致しない場合はエラーを返します。
+    pub fn new(value: u64, level: MeshLevel) -> Result<Self> {
+        let meshcode = MeshCode::try_from(value)?;
+        if meshcode.level != level {
+            return Err(JismeshError::UnexpectedMeshLevel(
+                value,
+                meshcode.level,
+                level,
+            ));
+        }
+        Ok(meshcode)
+    }
+
     /// 緯度経度からメッシュコードを生成する。
     pub fn try_from_latlng(lat: f64, lon: f64, level: MeshLevel) -> Result<Self> {
         let meshcode = to_meshcode(&[lat], &[lon], level)?;
@@ -21,31 +78,105 @@ impl MeshCode {
         Ok(meshcode.first().cloned().unwrap())
     }
 
+    /// 緯度経度から、すべての次数のメッシュコードを生成する。
+    /// 次数は `MeshLevel::iter()` の順番です。
+    pub fn all_levels(lat: f64, lon: f64) -> Result<Vec<(MeshLevel, MeshCode)>> {
+        MeshLevel::iter()
+            .map(|level| Ok((level, MeshCode::try_from_latlng(lat, lon, level)?)))
+            .collect()
+    }
+
     /// あるメッシュコードの次数を下げる（親メッシュコードを取得する）ために使ってください。
-    /// 現在は、 Lv3 -> Lv2 -> Lv1 のみ対応しております。
+    ///
+    /// `ancestor_at` と同じです。互換性のために残しています。
     pub fn lower_level(&self, level: MeshLevel) -> Result<MeshCode> {
+        self.ancestor_at(level)
+    }
+
+    /// このメッシュを含む、指定次数の祖先メッシュコードを取得する。
+    /// 指定次数がこのメッシュと同じ場合は、このメッシュコードをそのまま返します。
+    ///
+    /// 拡張統合地域メッシュ（40倍〜2倍）を含む全ての次数に対応しています。
+    /// 指定次数が `MeshLevel::direct_parent` をたどった先にある場合は、末尾の桁を切り捨てて求めます。
+    /// それ以外でも、このメッシュが指定次数のメッシュにぴったり収まる場合（例: Lv3 → X5 ）は、
+    /// それを含むメッシュコードを返します。
+    ///
+    /// 指定次数の方が細かい場合や、メッシュの境界がそろわず複数のメッシュにまたがる場合
+    /// （例: Lv3 → X2_5 ）はエラーを返します。
+    pub fn ancestor_at(&self, level: MeshLevel) -> Result<MeshCode> {
         if level > self.level {
             return Err(JismeshError::InvalidMeshLevelForLowerLevel(
                 self.level, level,
             ));
         }
 
-        let new_value = match (self.level, level) {
-            (x, y) if x == y => Ok(self.value),
-            (MeshLevel::Lv3, MeshLevel::Lv2) => Ok(self.value / 100),
-            (MeshLevel::Lv2, MeshLevel::Lv1) => Ok(self.value / 100),
-            (MeshLevel::Lv3, MeshLevel::Lv1) => Ok(self.value / 10000),
-            _ => Err(JismeshError::UnsupportedMeshLevelConversion(
+        // Each level appends digits to the code of its direct parent, so an ancestor in the
+        // digit hierarchy is a prefix of this code
+        let mut ancestor = Some(self.level);
+        while let Some(current) = ancestor
+            && current != level
+        {
+            ancestor = current.direct_parent();
+        }
+        if ancestor.is_some() {
+            return Ok(MeshCode {
+                value: self.value / 10_u64.pow(code_digits(self.level) - code_digits(level)),
+                level,
+            });
+        }
+
+        // Levels in other branches of the hierarchy can't be found from the digits, but their
+        // boundaries may still line up on the global grid
+        if !self.can_nest_into(level) {
+            return Err(JismeshError::UnsupportedMeshLevelConversion(
                 self.level, level,
-            )),
-        }?;
+            ));
+        }
+        let ratio = grid::cells_per_lv1(self.level) / grid::cells_per_lv1(level);
+        let (row, col) = self.global_index();
+        MeshCode::from_global_index(row / ratio, col / ratio, level)
+    }
 
-        Ok(MeshCode {
-            value: new_value,
-            level,
+    /// 3次メッシュ（8桁）に正規化したメッシュコードを取得する。
+    /// 3次メッシュのみを扱う既存システムとの連携に使えます。
+    ///
+    /// * 3次メッシュより細かい場合は、それを含む3次メッシュコードを返します。
+    /// * 3次メッシュより粗い場合（拡張統合地域メッシュを含む）は、複数の3次メッシュにまたがるため
+    ///   エラーを返します。含まれる3次メッシュが必要な場合は `to_intersects` を使ってください。
+    pub fn to_lv3(&self) -> Result<MeshCode> {
+        self.lower_level(MeshLevel::Lv3)
+    }
+
+    /// 指定された次数に丸めたメッシュコードを取得する。
+    ///
+    /// * 指定次数がこのメッシュより粗い場合は、親メッシュコードを返します。
+    /// * 指定次数がこのメッシュと同じ、またはより細かい場合は、このメッシュコードをそのまま返します。
+    ///
+    /// 親メッシュコードは `lower_level` で求めます。メッシュの境界がそろわない次数の組み合わせ
+    /// （例: Lv3 → X2_5 ）の場合は、中心点を含む指定次数のメッシュコードを返します。
+    /// 中心点の座標を求められないメッシュコード（例: 経度の桁が範囲外の 538000 ）の場合は、
+    /// エラーを返します。
+    pub fn clamp_to_level(&self, level: MeshLevel) -> Result<MeshCode> {
+        if unit_lat(level) <= unit_lat(self.level) {
+            return Ok(*self);
+        }
+
+        self.lower_level(level).or_else(|_| {
+            let (lat, lon) = self.point(0.5, 0.5)?;
+            MeshCode::try_from_latlng(lat, lon, level)
         })
     }
 
+    /// 1つ上の階層（`MeshLevel::direct_parent`）の親メッシュコードを取得する。
+    /// 1次メッシュの場合は None を返します。
+    /// 親メッシュコードを求められない場合は、 `clamp_to_level` と同じくエラーを返します。
+    pub fn parent(&self) -> Result<Option<MeshCode>> {
+        self.level
+            .direct_parent()
+            .map(|level| self.clamp_to_level(level))
+            .transpose()
+    }
+
     /// メッシュコードから緯度経度の座標を取得する。
     /// 緯度経度の座標は、lat/lon_multiplier で位置を調整できます。
     /// lat: 0.0, lon: 0.0 の場合は、メッシュコードの SW (南西) 端の座標を返します。
@@ -57,7 +188,183 @@ impl MeshCode {
         Ok((points[0][0], points[1][0]))
     }
 
+    /// メッシュの四隅または中心の座標を取得する。
+    /// `point(0.0, 1.0)` のように倍率を指定する代わりに、位置を名前で指定できます。
+    /// 返却値は (緯度, 経度) です。
+    pub fn corner(&self, corner: Corner) -> Result<(f64, f64)> {
+        let (lat_multiplier, lon_multiplier) = corner.multipliers();
+        self.point(lat_multiplier, lon_multiplier)
+    }
+
+    /// `point` と同じですが、 lat/lon_multiplier が 0.0〜1.0 の範囲外の場合はエラーを返します。
+    /// 返却される座標は、必ずメッシュ内（境界を含む）にあります。
+    /// メッシュの外に位置をずらしたい場合は `point` を使ってください。
+    pub fn point_checked(&self, lat_multiplier: f64, lon_multiplier: f64) -> Result<(f64, f64)> {
+        if !(0.0..=1.0).contains(&lat_multiplier) || !(0.0..=1.0).contains(&lon_multiplier) {
+            return Err(JismeshError::MultiplierOutOfRange(
+                lat_multiplier,
+                lon_multiplier,
+            ));
+        }
+        self.point(lat_multiplier, lon_multiplier)
+    }
+
+    /// メッシュの範囲を取得する。
+    /// 返却値は (南端の緯度, 西端の経度, 北端の緯度, 東端の経度) です。
+    pub fn bounds(&self) -> Result<(f64, f64, f64, f64)> {
+        let (lat_s, lon_w) = self.point(0.0, 0.0)?;
+        let (lat_n, lon_e) = self.point(1.0, 1.0)?;
+        Ok((lat_s, lon_w, lat_n, lon_e))
+    }
+
+    /// メッシュの中心点を取得する。 `point(0.5, 0.5)` と同じです。
+    /// 返却値は (緯度, 経度) です。
+    pub fn center(&self) -> Result<(f64, f64)> {
+        self.point(0.5, 0.5)
+    }
+
+    /// メッシュの中心点と、中心点から端までの距離（度）を取得する。
+    /// 返却値は ((中心の緯度, 中心の経度), (緯度方向の半分の幅, 経度方向の半分の幅)) です。
+    ///
+    /// メッシュコードが表す実際の位置はメッシュ内のどこかのため、位置の誤差（±）として使えます。
+    pub fn centroid_with_halfspan(&self) -> Result<((f64, f64), (f64, f64))> {
+        let (lat_s, lon_w, lat_n, lon_e) = self.bounds()?;
+        let half_lat = (lat_n - lat_s) / 2.0;
+        let half_lon = (lon_e - lon_w) / 2.0;
+        Ok(((lat_s + half_lat, lon_w + half_lon), (half_lat, half_lon)))
+    }
+
+    /// メッシュの外接円を取得する。
+    /// 返却値は ((中心の緯度, 中心の経度), 半径(m)) です。
+    /// 半径は中心から最も遠い角までの大円距離で、簡易的な距離フィルタに使えます。
+    pub fn bounding_circle_m(&self) -> Result<((f64, f64), f64)> {
+        let (lat, lon) = self.point(0.5, 0.5)?;
+        let mut radius: f64 = 0.0;
+        for (lat_multiplier, lon_multiplier) in [(0.0, 0.0), (0.0, 1.0), (1.0, 0.0), (1.0, 1.0)] {
+            let (corner_lat, corner_lon) = self.point(lat_multiplier, lon_multiplier)?;
+            radius = radius.max(geodesy::haversine_distance(
+                lat, lon, corner_lat, corner_lon,
+            ));
+        }
+        Ok(((lat, lon), radius))
+    }
+
+    /// メッシュの面積（m²）を取得する。
+    ///
+    /// WGS84 楕円体上で、メッシュの南北の緯線と東西の経線で囲まれた範囲の面積を、
+    /// 閉じた式（楕円体の面積要素の積分）で計算します。誤差は浮動小数点の丸め誤差のみで、
+    /// 1 m² よりも十分に小さいです。
+    /// 経度方向の幅は緯度が高いほど狭くなるため、同じ次数でも北のメッシュほど面積が小さくなります。
+    pub fn area_m2(&self) -> Result<f64> {
+        let (lat_s, lon_w, lat_n, lon_e) = self.bounds()?;
+        Ok(geodesy::ellipsoidal_rect_area(lat_s, lon_w, lat_n, lon_e))
+    }
+
+    /// このメッシュの中心点から、別のメッシュの中心点までの大圏距離（m）を取得する。
+    ///
+    /// 地球を平均半径 6,371,008.8 m の球とみなし、ハバーサイン公式で計算します。
+    /// 楕円体上の測地線距離との差は最大で 0.5% 程度で、近くのメッシュを絞り込む用途に向いています。
+    /// 次数が異なるメッシュ同士でも計算できます。
+    pub fn distance_to(&self, other: &MeshCode) -> Result<f64> {
+        let (lat0, lon0) = self.center()?;
+        let (lat1, lon1) = other.center()?;
+        Ok(geodesy::haversine_distance(lat0, lon0, lat1, lon1))
+    }
+
+    /// このメッシュの中心点から、別のメッシュの中心点までを緯度経度で線形補間した座標を取得する。
+    /// t: 0.0 の場合はこのメッシュの中心点、 t: 1.0 の場合は other の中心点を返します。
+    /// 返却値は (緯度, 経度) です。
+    pub fn lerp_centroid(&self, other: &MeshCode, t: f64) -> Result<(f64, f64)> {
+        let (lat0, lon0) = self.point(0.5, 0.5)?;
+        let (lat1, lon1) = other.point(0.5, 0.5)?;
+        Ok((lat0 + (lat1 - lat0) * t, lon0 + (lon1 - lon0) * t))
+    }
+
+    /// 地図上でラベルを配置する代表点を取得する。メッシュの中心点です。
+    /// 返却値は (緯度, 経度) です。
+    pub fn label_point(&self) -> Result<(f64, f64)> {
+        self.point(0.5, 0.5)
+    }
+
+    /// `label_point` と同じですが、中心点から緯度・経度方向にそれぞれ最大 jitter
+    /// （メッシュの一辺に対する割合、0.0〜0.5）だけずらした点を返します。
+    /// グリッド状に並んだラベルが一直線にそろって重なるのを避けるために使えます。
+    ///
+    /// ずらす量は `stable_hash` をシード値として決まるため、同じメッシュコードであれば
+    /// 常に同じ点を返します。 jitter が範囲外の場合はエラーを返します。
+    pub fn label_point_jittered(&self, jitter: f64) -> Result<(f64, f64)> {
+        if !(0.0..=0.5).contains(&jitter) {
+            return Err(JismeshError::JitterOutOfRange {
+                value: jitter,
+                min: 0.0,
+                max: 0.5,
+            });
+        }
+        let mut rng = random::SplitMix64::new(self.stable_hash());
+        let lat_multiplier = 0.5 + jitter * (rng.next_f64() * 2.0 - 1.0);
+        let lon_multiplier = 0.5 + jitter * (rng.next_f64() * 2.0 - 1.0);
+        self.point_checked(lat_multiplier, lon_multiplier)
+    }
+
+    /// メッシュの中心点が日本の範囲（`codes::JAPAN_BBOX`）内にあるかどうかを確認する。
+    /// 座標を計算できない不正なメッシュコードの場合は false を返します。
+    pub fn is_within_japan(&self) -> bool {
+        let (lat_s, lon_w, lat_n, lon_e) = crate::codes::JAPAN_BBOX;
+        match self.point(0.5, 0.5) {
+            Ok((lat, lon)) => (lat_s..lat_n).contains(&lat) && (lon_w..lon_e).contains(&lon),
+            Err(_) => false,
+        }
+    }
+
+    /// 緯度経度の点がメッシュ内にあるかどうかを確認する。
+    ///
+    /// `to_meshcode` の切り捨てと同じく、南端・西端の境界は含み、北端・東端の境界は含みません。
+    /// そのため、隣り合うメッシュの境界上の点は、どちらか一方のメッシュにのみ含まれます。
+    pub fn contains_point(&self, lat: f64, lon: f64) -> Result<bool> {
+        let (lat_s, lon_w, lat_n, lon_e) = self.bounds()?;
+        Ok((lat_s..lat_n).contains(&lat) && (lon_w..lon_e).contains(&lon))
+    }
+
+    /// メッシュ内にある緯度経度の点を含む、指定次数の子メッシュコードを取得する。
+    /// 点がこのメッシュの外にある場合や、指定次数がこのメッシュより粗い場合はエラーを返します。
+    pub fn child_at_point(&self, lat: f64, lon: f64, level: MeshLevel) -> Result<MeshCode> {
+        if unit_lat(level) > unit_lat(self.level) {
+            return Err(JismeshError::InvalidMeshLevelForHigherLevel(
+                level, self.level,
+            ));
+        }
+
+        let (lat_s, lon_w) = self.point(0.0, 0.0)?;
+        let (lat_n, lon_e) = self.point(1.0, 1.0)?;
+        if !(lat_s..lat_n).contains(&lat) || !(lon_w..lon_e).contains(&lon) {
+            return Err(JismeshError::PointOutsideMeshCode(lat, lon, self.value));
+        }
+
+        MeshCode::try_from_latlng(lat, lon, level)
+    }
+
+    /// 緯度経度の点が、メッシュ内のどの位置にあるかを 0〜1 の割合で取得する。
+    /// 返却値は (緯度方向の割合, 経度方向の割合) で、 (0.0, 0.0) が南西端、 (1.0, 1.0) が北東端です。
+    /// `point` の逆変換で、 `point(u, v)` に返却値を渡すと元の点に戻ります。
+    ///
+    /// 点がメッシュの外（境界を除く）にある場合はエラーを返します。
+    pub fn fractional_position(&self, lat: f64, lon: f64) -> Result<(f64, f64)> {
+        let (lat_s, lon_w) = self.point(0.0, 0.0)?;
+        let (lat_n, lon_e) = self.point(1.0, 1.0)?;
+        if !(lat_s..=lat_n).contains(&lat) || !(lon_w..=lon_e).contains(&lon) {
+            return Err(JismeshError::PointOutsideMeshCode(lat, lon, self.value));
+        }
+
+        Ok((
+            (lat - lat_s) / (lat_n - lat_s),
+            (lon - lon_w) / (lon_e - lon_w),
+        ))
+    }
+
     /// メッシュコードが指定されたメッシュコードを含むかどうかを確認する。
+    ///
+    /// 範囲の包含で判定するため、拡張統合地域メッシュと標準地域メッシュのように、
+    /// コードの桁が共通していない組み合わせ（例: X40 と Lv3）でも判定できます。
     pub fn contains(&self, code: &MeshCode) -> bool {
         if self.level == code.level {
             return self.value == code.value;
@@ -66,21 +373,113 @@ impl MeshCode {
             return false;
         }
 
-        // Check if the code is a lower level of this mesh code
-        let parent_code = code.lower_level(self.level);
-        match parent_code {
-            Ok(parent) => self.value == parent.value,
-            Err(_) => false,
-        }
+        let (row_s, col_w, row_n, col_e) = self.lv6_rect();
+        let (code_row_s, code_col_w, code_row_n, code_col_e) = code.lv6_rect();
+        row_s <= code_row_s && code_row_n <= row_n && col_w <= code_col_w && code_col_e <= col_e
     }
 
     /// メッシュコードが指定されたメッシュコードと交差するかどうかを確認する。
+    /// 境界で接しているだけの場合は交差しません。
     pub fn intersects(&self, other: &MeshCode) -> bool {
-        if self.level < other.level {
-            self.contains(other)
-        } else {
-            other.contains(self)
+        let (row_s, col_w, row_n, col_e) = self.lv6_rect();
+        let (other_row_s, other_col_w, other_row_n, other_col_e) = other.lv6_rect();
+        row_s < other_row_n && other_row_s < row_n && col_w < other_col_e && other_col_w < col_e
+    }
+
+    /// 区切り文字（ハイフン）を入れたメッシュコードの文字列を取得する。
+    /// 例: 3次メッシュ 53393599 は "5339-35-99" 、4次メッシュ 533935992 は "5339-35-99-2"
+    ///
+    /// 区切りの位置は、`MeshLevel::direct_parent` をたどった各階層で付け足される桁ごとです。
+    /// 拡張統合地域メッシュでは、末尾の識別用の数字も同じ区切りに含まれます
+    /// （例: 16倍メッシュ 5339467 は "5339-467" 、5倍メッシュ 5339354 は "5339-35-4"）。
+    /// `Display` は区切りのない数値のままです。
+    pub fn to_formatted(&self) -> String {
+        let digits = self.value.to_string();
+        let mut bounds = digit_group_ends(self.level);
+        bounds.insert(0, 0);
+        bounds
+            .windows(2)
+            .map(|w| &digits[w[0]..w[1]])
+            .collect::<Vec<_>>()
+            .join("-")
+    }
+
+    /// メッシュコードの 10 進数の各桁を、上の桁から順に取得する。
+    /// 例: 3次メッシュ 53393599 は [5, 3, 3, 9, 3, 5, 9, 9]
+    ///
+    /// 長さは次数ごとに決まっていて、 `digit_len` と同じです。
+    /// 1次〜6次の階層では親メッシュの桁が子メッシュの先頭と一致するので、
+    /// トライ木などの前方一致による空間インデックスのキーに使えます。
+    pub fn digits(&self) -> Vec<u8> {
+        let len = self.digit_len() as u32;
+        (0..len)
+            .map(|i| slice(&[self.value], i, i + 1)[0])
+            .collect()
+    }
+
+    /// メッシュコードの桁数を取得する。次数ごとに決まっています（例: 3次メッシュは 8）。
+    pub fn digit_len(&self) -> u8 {
+        code_digits(self.level) as u8
+    }
+
+    /// `to_formatted` の形式の文字列からメッシュコードを生成する。
+    ///
+    /// 区切り文字にはハイフンと空白が使えます。区切り文字のない数字のみの文字列も受け付けますが、
+    /// 区切りの位置が `to_formatted` と異なる場合はエラーを返します。
+    pub fn from_formatted(s: &str) -> Result<MeshCode> {
+        let invalid = || JismeshError::InvalidMeshCode(s.to_string());
+        let groups: Vec<&str> = s.trim().split(['-', ' ']).collect();
+        if groups
+            .iter()
+            .any(|group| group.is_empty() || !group.bytes().all(|b| b.is_ascii_digit()))
+        {
+            return Err(invalid());
+        }
+        let meshcode: MeshCode = groups.concat().parse().map_err(|_| invalid())?;
+        if groups.len() > 1 && meshcode.to_formatted() != groups.join("-") {
+            return Err(invalid());
+        }
+        Ok(meshcode)
+    }
+
+    /// 文字列からメッシュコードを生成する。 `from_str` と異なり、文字列の桁数をそのまま使います。
+    ///
+    /// メッシュコードの次数は桁数で判定するため、先頭の 0 は意味を持ちます。
+    /// `from_str` は数値として読み込むので、例えば "053393599" の先頭の 0 を捨てて
+    /// 8桁の 3次メッシュコードとして解釈してしまいます。
+    /// 緯度 6.67 度未満のメッシュコードは 0 から始まりますが、 `MeshCode` は値を u64 で持つため
+    /// 表せません。この関数は、そのような桁数が変わってしまう文字列をエラーにします。
+    ///
+    /// 前後の空白は無視します。数字以外の文字（符号を含む）がある場合もエラーを返します。
+    pub fn from_str_exact(s: &str) -> Result<MeshCode> {
+        let invalid = || JismeshError::InvalidMeshCode(s.to_string());
+        let digits = s.trim();
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
         }
+        let value: u64 = digits.parse().map_err(|_| invalid())?;
+        if num_digits(value) as usize != digits.len() {
+            return Err(invalid());
+        }
+        value.try_into()
+    }
+
+    /// プラットフォームや `Hash` の実装に依存しない、安定したハッシュ値を取得する。
+    ///
+    /// メッシュコードの値（リトルエンディアン 8 バイト）と次数の値（`MeshLevel` の数値、
+    /// リトルエンディアン 4 バイト）を 64bit FNV-1a でハッシュした値です。
+    /// この仕様は変更しないので、ディスク上のハッシュインデックスのキーなどに使えます。
+    pub fn stable_hash(&self) -> u64 {
+        const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+        const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+        self.value
+            .to_le_bytes()
+            .into_iter()
+            .chain((self.level as u32).to_le_bytes())
+            .fold(FNV_OFFSET_BASIS, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+            })
     }
 }
 
@@ -96,11 +495,25 @@ impl TryFrom<u64> for MeshCode {
     }
 }
 
+impl TryFrom<(f64, f64, MeshLevel)> for MeshCode {
+    type Error = error::JismeshError;
+
+    /// (緯度, 経度, 次数) からメッシュコードを生成する。 `try_from_latlng` と同じです。
+    fn try_from((lat, lon, level): (f64, f64, MeshLevel)) -> Result<Self> {
+        MeshCode::try_from_latlng(lat, lon, level)
+    }
+}
+
 impl FromStr for MeshCode {
     type Err = error::JismeshError;
 
+    /// 文字列を数値として読み込んでメッシュコードを生成する。前後の空白は無視します。
+    ///
+    /// 数値として読み込むため、先頭の 0 は捨てられます。次数は桁数で判定するので、
+    /// 先頭の 0 が意味を持つ可能性がある入力には `MeshCode::from_str_exact` を使ってください。
     fn from_str(value: &str) -> Result<Self> {
         let value = value
+            .trim()
             .parse::<u64>()
             .map_err(|_| JismeshError::InvalidMeshCode(value.to_string()))?;
         value.try_into()
@@ -125,6 +538,39 @@ impl PartialEq<u64> for MeshCode {
     }
 }
 
+impl PartialEq<MeshCode> for u64 {
+    fn eq(&self, other: &MeshCode) -> bool {
+        *self == other.value
+    }
+}
+
+/// Number of digits of a mesh code at each level.
+fn code_digits(level: MeshLevel) -> u32 {
+    match level {
+        MeshLevel::Lv1 => 4,
+        MeshLevel::X40 => 5,
+        MeshLevel::Lv2 => 6,
+        MeshLevel::X20 | MeshLevel::X16 | MeshLevel::X8 | MeshLevel::X5 => 7,
+        MeshLevel::Lv3 => 8,
+        MeshLevel::X4 | MeshLevel::X2_5 | MeshLevel::X2 | MeshLevel::Lv4 => 9,
+        MeshLevel::Lv5 => 10,
+        MeshLevel::Lv6 => 11,
+    }
+}
+
+/// End positions of the digit groups of a mesh code, one group per level of the
+/// `MeshLevel::direct_parent` hierarchy from Lv1 down to `level`.
+fn digit_group_ends(level: MeshLevel) -> Vec<usize> {
+    let mut ends = vec![code_digits(level) as usize];
+    let mut current = level;
+    while let Some(parent) = current.direct_parent() {
+        ends.push(code_digits(parent) as usize);
+        current = parent;
+    }
+    ends.reverse();
+    ends
+}
+
 /// Converts latitude & longitude to a meshcode.
 /// 緯度経度から指定次の地域メッシュコードを算出する。
 ///
@@ -132,18 +578,7 @@ impl PartialEq<u64> for MeshCode {
 /// * lat: 世界測地系の緯度(度単位)
 /// * lon: 世界測地系の経度(度単位)
 pub fn to_meshcode(lat: &[f64], lon: &[f64], level: MeshLevel) -> Result<Vec<MeshCode>> {
-    // Validate bounds for all values in the arrays
-    for &lat_val in lat.iter() {
-        if !(0.0..66.66).contains(&lat_val) {
-            return Err(JismeshError::LatitudeOutOfBounds(lat_val));
-        }
-    }
-
-    for &lon_val in lon.iter() {
-        if !(100.0..180.0).contains(&lon_val) {
-            return Err(JismeshError::LongitudeOutOfBounds(lon_val));
-        }
-    }
+    validate_latlng(lat, lon)?;
 
     // Create output vector
     let result_len = lat.len().max(lon.len());
@@ -152,30 +587,197 @@ pub fn to_meshcode(lat: &[f64], lon: &[f64], level: MeshLevel) -> Result<Vec<Mes
     for i in 0..result_len {
         let lat_val = lat[i % lat.len()];
         let lon_val = lon[i % lon.len()];
-
-        // Calculate mesh code based on level
-        let meshcode = match level {
-            MeshLevel::Lv1 => meshcode_lv1(lat_val, lon_val),
-            MeshLevel::X40 => meshcode_40000(lat_val, lon_val),
-            MeshLevel::X20 => meshcode_20000(lat_val, lon_val),
-            MeshLevel::X16 => meshcode_16000(lat_val, lon_val),
-            MeshLevel::Lv2 => meshcode_lv2(lat_val, lon_val),
-            MeshLevel::X8 => meshcode_8000(lat_val, lon_val),
-            MeshLevel::X5 => meshcode_5000(lat_val, lon_val),
-            MeshLevel::X4 => meshcode_4000(lat_val, lon_val),
-            MeshLevel::X2_5 => meshcode_2500(lat_val, lon_val),
-            MeshLevel::X2 => meshcode_2000(lat_val, lon_val),
-            MeshLevel::Lv3 => meshcode_lv3(lat_val, lon_val),
-            MeshLevel::Lv4 => meshcode_lv4(lat_val, lon_val),
-            MeshLevel::Lv5 => meshcode_lv5(lat_val, lon_val),
-            MeshLevel::Lv6 => meshcode_lv6(lat_val, lon_val),
-        };
-        result.push(meshcode);
+        result.push(meshcode_at(lat_val, lon_val, level));
     }
 
     Ok(result)
 }
 
+/// Converts latitude & longitude to a meshcode on all available cores.
+/// 緯度経度から指定次の地域メッシュコードを、複数のスレッドで並列に算出する。
+///
+/// `to_meshcode` と同じ結果を返します。範囲外の値がある場合は、並列化する前に検証するため、
+/// `to_meshcode` と同じく最初の範囲外の値のエラーを返します。緯度と経度の一方だけが空の場合も
+/// `to_meshcode` と同じくエラーを返します。
+///
+/// Args:
+/// * lat: 世界測地系の緯度(度単位)
+/// * lon: 世界測地系の経度(度単位)
+#[cfg(feature = "rayon")]
+pub fn to_meshcode_par(lat: &[f64], lon: &[f64], level: MeshLevel) -> Result<Vec<MeshCode>> {
+    use rayon::prelude::*;
+
+    validate_latlng(lat, lon)?;
+
+    let result_len = lat.len().max(lon.len());
+    Ok((0..result_len)
+        .into_par_iter()
+        .map(|i| meshcode_at(lat[i % lat.len()], lon[i % lon.len()], level))
+        .collect())
+}
+
+/// Converts latitude & longitude to a meshcode, resolving points on mesh boundaries with `boundary`.
+/// 緯度経度から指定次の地域メッシュコードを算出する。境界線上の点は `boundary` に従って割り当てる。
+///
+/// `BoundaryRule::LowerInclusive` gives the same result as [`to_meshcode`]. With
+/// `BoundaryRule::UpperInclusive`, a point on a south (west) edge is assigned to the mesh to the
+/// south (west), so a point on a corner goes to the mesh whose NE corner it is.
+///
+/// Args:
+/// * lat: 世界測地系の緯度(度単位)
+/// * lon: 世界測地系の経度(度単位)
+/// * level: 次数
+/// * boundary: 境界線上の点の割り当て規則
+///
+/// # Errors
+/// * Returns the same errors as [`to_meshcode`], including `EmptyInput` when exactly one of
+///   `lat` and `lon` is empty
+/// * With `BoundaryRule::UpperInclusive`, returns an out of bounds error for points on the
+///   southern (lat 0) or western (lon 100) edge of the domain, which have no mesh to fall back to
+pub fn to_meshcode_with_boundary(
+    lat: &[f64],
+    lon: &[f64],
+    level: MeshLevel,
+    boundary: BoundaryRule,
+) -> Result<Vec<MeshCode>> {
+    let codes = to_meshcode(lat, lon, level)?;
+    if boundary == BoundaryRule::LowerInclusive {
+        return Ok(codes);
+    }
+
+    codes
+        .into_iter()
+        .enumerate()
+        .map(|(i, code)| {
+            let (lat_index, lon_index) = (i % lat.len(), i % lon.len());
+            let (lat_val, lon_val) = (lat[lat_index], lon[lon_index]);
+            let (lat_s, lon_w, _, _) = code.bounds()?;
+            let (row, col) = code.global_index();
+            let d_row = if lat_val <= lat_s { -1 } else { 0 };
+            let d_col = if lon_val <= lon_w { -1 } else { 0 };
+            if d_row < 0 && row == 0 {
+                return Err(JismeshError::LatitudeOutOfBounds {
+                    value: lat_val,
+                    index: lat_index,
+                });
+            }
+            if d_col < 0 && col == 0 {
+                return Err(JismeshError::LongitudeOutOfBounds {
+                    value: lon_val,
+                    index: lon_index,
+                });
+            }
+            if d_row == 0 && d_col == 0 {
+                Ok(code)
+            } else {
+                code.step(d_row, d_col)
+            }
+        })
+        .collect()
+}
+
+/// Checks whether a coordinate is inside the domain covered by the JIS mesh system.
+///
+/// The standard regional mesh is defined for Japan only: the latitude must be in
+/// `0 <= lat < 66.66` and the longitude in `100 <= lon < 180`. Points for which this
+/// returns `true` can be passed to [`to_meshcode`] without triggering a bounds error.
+///
+/// # Arguments
+/// * `lat` - Latitude in degrees
+/// * `lon` - Longitude in degrees
+///
+/// # Returns
+/// `true` if the coordinate can be converted to a mesh code
+pub fn is_in_valid_range(lat: f64, lon: f64) -> bool {
+    is_lat_in_range(lat) && is_lon_in_range(lon)
+}
+
+fn is_lat_in_range(lat: f64) -> bool {
+    (0.0..66.66).contains(&lat)
+}
+
+fn is_lon_in_range(lon: f64) -> bool {
+    (100.0..180.0).contains(&lon)
+}
+
+/// Validates bounds for all values in the arrays, reporting the first invalid latitude,
+/// then the first invalid longitude.
+///
+/// Exactly one empty array is rejected too, as there is nothing to broadcast it to.
+pub(crate) fn validate_latlng(lat: &[f64], lon: &[f64]) -> Result<()> {
+    if lat.is_empty() != lon.is_empty() {
+        return Err(JismeshError::EmptyInput);
+    }
+    if let Some(index) = lat.iter().position(|&v| !is_lat_in_range(v)) {
+        return Err(JismeshError::LatitudeOutOfBounds {
+            value: lat[index],
+            index,
+        });
+    }
+    if let Some(index) = lon.iter().position(|&v| !is_lon_in_range(v)) {
+        return Err(JismeshError::LongitudeOutOfBounds {
+            value: lon[index],
+            index,
+        });
+    }
+    Ok(())
+}
+
+/// Validates the corners of a bounding box, reporting index 0 for the south-west corner and 1
+/// for the north-east corner.
+///
+/// The south-west corner must pass [`is_in_valid_range`]. The north-east corner may also lie
+/// on the northern or eastern edge of the domain, so that a bounding box can reach the last
+/// row and column of meshes.
+pub(crate) fn validate_bbox_corners(lat_s: f64, lon_w: f64, lat_n: f64, lon_e: f64) -> Result<()> {
+    let corners = [(lat_s, lon_w, false), (lat_n, lon_e, true)];
+    for (index, (lat, lon, edge_allowed)) in corners.into_iter().enumerate() {
+        if is_in_valid_range(lat, lon) {
+            continue;
+        }
+        if !(is_lat_in_range(lat) || (edge_allowed && lat == 66.66)) {
+            return Err(JismeshError::LatitudeOutOfBounds { value: lat, index });
+        }
+        if !(is_lon_in_range(lon) || (edge_allowed && lon == 180.0)) {
+            return Err(JismeshError::LongitudeOutOfBounds { value: lon, index });
+        }
+    }
+    Ok(())
+}
+
+/// Calculates the mesh code of a validated coordinate.
+fn meshcode_at(lat: f64, lon: f64, level: MeshLevel) -> MeshCode {
+    match level {
+        MeshLevel::Lv1 => meshcode_lv1(lat, lon),
+        MeshLevel::X40 => meshcode_40000(lat, lon),
+        MeshLevel::X20 => meshcode_20000(lat, lon),
+        MeshLevel::X16 => meshcode_16000(lat, lon),
+        MeshLevel::Lv2 => meshcode_lv2(lat, lon),
+        MeshLevel::X8 => meshcode_8000(lat, lon),
+        MeshLevel::X5 => meshcode_5000(lat, lon),
+        MeshLevel::X4 => meshcode_4000(lat, lon),
+        MeshLevel::X2_5 => meshcode_2500(lat, lon),
+        MeshLevel::X2 => meshcode_2000(lat, lon),
+        MeshLevel::Lv3 => meshcode_lv3(lat, lon),
+        MeshLevel::Lv4 => meshcode_lv4(lat, lon),
+        MeshLevel::Lv5 => meshcode_lv5(lat, lon),
+        MeshLevel::Lv6 => meshcode_lv6(lat, lon),
+    }
+}
+
+/// Converts (latitude, longitude) pairs to meshcodes.
+/// 緯度経度の組から指定次の地域メッシュコードを算出する。
+///
+/// `to_meshcode` と同じですが、緯度・経度を別々の配列ではなく (緯度, 経度) の組で受け取るため、
+/// 配列の長さの不一致や並びのずれが起きません。
+///
+/// Args:
+/// * points: 世界測地系の (緯度, 経度) (度単位)
+pub fn to_meshcode_pairs(points: &[(f64, f64)], level: MeshLevel) -> Result<Vec<MeshCode>> {
+    let (lat, lon): (Vec<f64>, Vec<f64>) = points.iter().copied().unzip();
+    to_meshcode(&lat, &lon, level)
+}
+
 // Helper functions for calculating meshcodes at various levels
 fn meshcode_lv1(lat: f64, lon: f64) -> MeshCode {
     let rem_lat_lv0 = lat;
@@ -214,6 +816,8 @@ fn meshcode_20000(lat: f64, lon: f64) -> MeshCode {
     }
 }
 
+// The 5x5 divisions of X16 and X2 are numbered with even digits (0, 2, 4, 6, 8), so the
+// index is multiplied by 2 here and divided by 2 when decoding in `to_meshpoint`.
 fn meshcode_16000(lat: f64, lon: f64) -> MeshCode {
     let base = meshcode_lv1(lat, lon);
     let rem_lat_lv1 = lat % UNIT_LAT_LV1;
@@ -291,6 +895,7 @@ fn meshcode_2500(lat: f64, lon: f64) -> MeshCode {
     }
 }
 
+// See `meshcode_16000` for the even digit convention.
 fn meshcode_2000(lat: f64, lon: f64) -> MeshCode {
     let base = meshcode_lv2(lat, lon);
     let rem_lat_lv2 = lat % UNIT_LAT_LV1 % UNIT_LAT_LV2;
@@ -433,6 +1038,116 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_to_meshcode_pairs() {
+        let points = [(35.658581, 139.745433), (34.987574, 135.759363)];
+        let lat: Vec<f64> = points.iter().map(|p| p.0).collect();
+        let lon: Vec<f64> = points.iter().map(|p| p.1).collect();
+        for level in MeshLevel::iter() {
+            assert_eq!(
+                to_meshcode_pairs(&points, level).unwrap(),
+                to_meshcode(&lat, &lon, level).unwrap(),
+                "Failed for {:?}",
+                level
+            );
+        }
+
+        assert!(to_meshcode_pairs(&[], MeshLevel::Lv3).unwrap().is_empty());
+        assert_eq!(
+            to_meshcode_pairs(&[(35.0, 139.0), (70.0, 139.0)], MeshLevel::Lv3),
+            Err(JismeshError::LatitudeOutOfBounds {
+                value: 70.0,
+                index: 1,
+            })
+        );
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_to_meshcode_par() {
+        let n = 10_000;
+        let lat: Vec<f64> = (0..n).map(|i| 30.0 + i as f64 * 0.0011).collect();
+        let lon: Vec<f64> = (0..n).map(|i| 130.0 + i as f64 * 0.0017).collect();
+        for level in MeshLevel::iter() {
+            assert_eq!(
+                to_meshcode_par(&lat, &lon, level).unwrap(),
+                to_meshcode(&lat, &lon, level).unwrap(),
+                "Failed for {:?}",
+                level
+            );
+        }
+
+        // Broadcasting a single longitude works the same way
+        assert_eq!(
+            to_meshcode_par(&lat, &[139.7], MeshLevel::Lv3).unwrap(),
+            to_meshcode(&lat, &[139.7], MeshLevel::Lv3).unwrap()
+        );
+
+        // The first invalid value is reported, regardless of scheduling
+        let mut lat = lat;
+        lat[5000] = 70.0;
+        lat[9000] = -1.0;
+        assert_eq!(
+            to_meshcode_par(&lat, &lon, MeshLevel::Lv3),
+            Err(JismeshError::LatitudeOutOfBounds {
+                value: 70.0,
+                index: 5000,
+            })
+        );
+
+        // Exactly one empty input has nothing to broadcast to
+        assert_eq!(
+            to_meshcode_par(&[], &[139.7], MeshLevel::Lv3),
+            Err(JismeshError::EmptyInput)
+        );
+        assert_eq!(
+            to_meshcode_par(&[35.6], &[], MeshLevel::Lv3),
+            Err(JismeshError::EmptyInput)
+        );
+        assert_eq!(to_meshcode_par(&[], &[], MeshLevel::Lv3), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_meshcode_new() {
+        let meshcode = MeshCode::new(5339, MeshLevel::Lv1).unwrap();
+        assert_eq!(meshcode, MeshCode::try_from(5339).unwrap());
+
+        let meshcode = MeshCode::new(533935446, MeshLevel::X2_5).unwrap();
+        assert_eq!(meshcode.level, MeshLevel::X2_5);
+
+        assert_eq!(
+            MeshCode::new(5339, MeshLevel::Lv2),
+            Err(JismeshError::UnexpectedMeshLevel(
+                5339,
+                MeshLevel::Lv1,
+                MeshLevel::Lv2
+            ))
+        );
+        assert!(MeshCode::new(5, MeshLevel::Lv1).is_err());
+    }
+
+    #[test]
+    fn test_meshcode_all_levels() {
+        let lat = 35.658581;
+        let lon = 139.745433;
+        let codes = MeshCode::all_levels(lat, lon).unwrap();
+        assert_eq!(codes.len(), 14);
+
+        let lv1 = codes.iter().find(|(l, _)| *l == MeshLevel::Lv1).unwrap().1;
+        assert_eq!(
+            lv1,
+            MeshCode::try_from_latlng(lat, lon, MeshLevel::Lv1).unwrap()
+        );
+        let lv3 = codes.iter().find(|(l, _)| *l == MeshLevel::Lv3).unwrap().1;
+        assert_eq!(lv3, to_meshcode(&[lat], &[lon], MeshLevel::Lv3).unwrap()[0]);
+
+        for (level, code) in codes {
+            assert_eq!(code.level, level);
+        }
+
+        assert!(MeshCode::all_levels(-1.0, lon).is_err());
+    }
+
     #[test]
     fn test_meshcode_try_from_u64() {
         // Test Level 1 mesh code
@@ -451,6 +1166,38 @@ mod tests {
         assert_eq!(meshcode.level, MeshLevel::Lv3);
     }
 
+    #[test]
+    fn test_meshcode_try_from_tuple() {
+        let meshcode: MeshCode = (35.658581, 139.745433, MeshLevel::Lv3).try_into().unwrap();
+        assert_eq!(meshcode, MeshCode::try_from(53393599).unwrap());
+
+        let codes: Result<Vec<MeshCode>> = [
+            (35.658581, 139.745433, MeshLevel::Lv1),
+            (34.987574, 135.759363, MeshLevel::Lv2),
+        ]
+        .into_iter()
+        .map(MeshCode::try_from)
+        .collect();
+        assert_eq!(codes.unwrap(), vec![5339, 523536]);
+
+        let result: Result<MeshCode> = (80.0, 139.745433, MeshLevel::Lv3).try_into();
+        assert_eq!(
+            result,
+            Err(JismeshError::LatitudeOutOfBounds {
+                value: 80.0,
+                index: 0,
+            })
+        );
+        let result: Result<MeshCode> = (35.658581, 190.0, MeshLevel::Lv3).try_into();
+        assert_eq!(
+            result,
+            Err(JismeshError::LongitudeOutOfBounds {
+                value: 190.0,
+                index: 0,
+            })
+        );
+    }
+
     #[test]
     fn test_meshcode_from_meshcode_to_u64() {
         let meshcode = MeshCode {
@@ -485,6 +1232,11 @@ mod tests {
             (MeshLevel::Lv3, MeshLevel::Lv2, 45304421, 453044),
             (MeshLevel::Lv2, MeshLevel::Lv1, 453044, 4530),
             (MeshLevel::Lv3, MeshLevel::Lv1, 45304421, 4530),
+            (MeshLevel::Lv6, MeshLevel::Lv5, 53393599212, 5339359921),
+            (MeshLevel::Lv6, MeshLevel::Lv4, 53393599212, 533935992),
+            (MeshLevel::Lv6, MeshLevel::Lv3, 53393599212, 53393599),
+            (MeshLevel::Lv5, MeshLevel::Lv2, 5339359921, 533935),
+            (MeshLevel::Lv4, MeshLevel::Lv1, 533935992, 5339),
         ];
         for (from, to, input_value, expected_value) in test_cases {
             let meshcode = MeshCode::try_from(input_value).unwrap();
@@ -513,29 +1265,225 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_meshcode_lower_levels_extended() {
+        // Ancestors in the digit hierarchy drop the digits each level appends
+        let test_cases = vec![
+            (53392, MeshLevel::X40, MeshLevel::Lv1, 5339),
+            (5339235, MeshLevel::X20, MeshLevel::X40, 53392),
+            (5339235, MeshLevel::X20, MeshLevel::Lv1, 5339),
+            (5339467, MeshLevel::X16, MeshLevel::Lv1, 5339),
+            (5339476, MeshLevel::X8, MeshLevel::Lv1, 5339),
+            (533947637, MeshLevel::X4, MeshLevel::X8, 5339476),
+            (533947637, MeshLevel::X4, MeshLevel::Lv1, 5339),
+            (5339354, MeshLevel::X5, MeshLevel::Lv2, 533935),
+            (533935446, MeshLevel::X2_5, MeshLevel::X5, 5339354),
+            (533935446, MeshLevel::X2_5, MeshLevel::Lv2, 533935),
+            (533935885, MeshLevel::X2, MeshLevel::Lv2, 533935),
+            // Other branches, found on the global grid
+            (53393599, MeshLevel::Lv3, MeshLevel::X2, 533935885),
+            (53393599, MeshLevel::Lv3, MeshLevel::X5, 5339354),
+            (53393599, MeshLevel::Lv3, MeshLevel::X40, 53392),
+            (53393599212, MeshLevel::Lv6, MeshLevel::X20, 5339235),
+            (533935, MeshLevel::Lv2, MeshLevel::X40, 53392),
+            (5339476, MeshLevel::X8, MeshLevel::X16, 5339467),
+            (533947637, MeshLevel::X4, MeshLevel::X20, 5339235),
+        ];
+        for (input_value, from, to, expected_value) in test_cases {
+            let meshcode = MeshCode::try_from(input_value).unwrap();
+            assert_eq!(meshcode.level, from);
+
+            let result = meshcode.lower_level(to).unwrap();
+            assert_eq!(result, MeshCode::new(expected_value, to).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_meshcode_lower_level_every_nesting_pair() {
+        // Every geometrically valid pair agrees with the mesh containing the center point
+        for (lat, lon) in [(35.658581, 139.745433), (34.987574, 135.759363)] {
+            for from in MeshLevel::iter() {
+                let meshcode = MeshCode::try_from_latlng(lat, lon, from).unwrap();
+                for to in MeshLevel::iter().filter(|to| *to <= from) {
+                    let (center_lat, center_lon) = meshcode.point(0.5, 0.5).unwrap();
+                    let expected = MeshCode::try_from_latlng(center_lat, center_lon, to).unwrap();
+                    if meshcode.can_nest_into(to) {
+                        assert_eq!(
+                            meshcode.lower_level(to),
+                            Ok(expected),
+                            "Failed for {} to {}",
+                            from,
+                            to
+                        );
+                    } else {
+                        assert_eq!(
+                            meshcode.lower_level(to),
+                            Err(JismeshError::UnsupportedMeshLevelConversion(from, to))
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_meshcode_ancestor_at() {
+        let test_cases = vec![
+            (45304421, MeshLevel::Lv2, 453044),
+            (453044, MeshLevel::Lv1, 4530),
+            (45304421, MeshLevel::Lv1, 4530),
+            (53393599212, MeshLevel::Lv3, 53393599),
+            (533935446, MeshLevel::X5, 5339354),
+            (53393599, MeshLevel::X2, 533935885),
+        ];
+        for (input_value, level, expected_value) in test_cases {
+            let meshcode = MeshCode::try_from(input_value).unwrap();
+            let ancestor = meshcode.ancestor_at(level).unwrap();
+            assert_eq!(ancestor, MeshCode::new(expected_value, level).unwrap());
+        }
+
+        // A Lv6 mesh nests into every level
+        let meshcode = MeshCode::try_from(53393599212).unwrap();
+        let ancestors = [
+            (MeshLevel::Lv1, 5339),
+            (MeshLevel::X40, 53392),
+            (MeshLevel::X20, 5339235),
+            (MeshLevel::X16, 5339467),
+            (MeshLevel::Lv2, 533935),
+            (MeshLevel::X8, 5339476),
+            (MeshLevel::X5, 5339354),
+            (MeshLevel::X4, 533947637),
+            (MeshLevel::X2_5, 533935446),
+            (MeshLevel::X2, 533935885),
+            (MeshLevel::Lv3, 53393599),
+            (MeshLevel::Lv4, 533935992),
+            (MeshLevel::Lv5, 5339359921),
+            (MeshLevel::Lv6, 53393599212),
+        ];
+        for (level, expected_value) in ancestors {
+            assert_eq!(
+                meshcode.ancestor_at(level).unwrap(),
+                MeshCode::new(expected_value, level).unwrap(),
+                "Failed for {}",
+                level
+            );
+        }
+
+        // An X2 mesh doesn't line up with X5 and X2_5
+        let meshcode = MeshCode::try_from(533935885).unwrap();
+        assert_eq!(meshcode.ancestor_at(MeshLevel::X4).unwrap(), 533947637);
+        assert_eq!(meshcode.ancestor_at(MeshLevel::X16).unwrap(), 5339467);
+        assert_eq!(
+            meshcode.ancestor_at(MeshLevel::X5),
+            Err(JismeshError::UnsupportedMeshLevelConversion(
+                MeshLevel::X2,
+                MeshLevel::X5
+            ))
+        );
+
+        let meshcode = MeshCode::try_from(5339).unwrap();
+        assert_eq!(
+            meshcode.ancestor_at(MeshLevel::Lv2),
+            Err(JismeshError::InvalidMeshLevelForLowerLevel(
+                MeshLevel::Lv1,
+                MeshLevel::Lv2
+            ))
+        );
+    }
+
     #[test]
     fn test_meshcode_to_lower_unsupported_conversion() {
+        // A Lv3 mesh may straddle two X2_5 meshes
         let meshcode = MeshCode {
-            value: 53392,
-            level: MeshLevel::X40,
+            value: 53393599,
+            level: MeshLevel::Lv3,
         };
-        let result = meshcode.lower_level(MeshLevel::Lv1);
+        let result = meshcode.lower_level(MeshLevel::X2_5);
         assert!(result.is_err());
         match result.unwrap_err() {
             JismeshError::UnsupportedMeshLevelConversion(from, to) => {
-                assert_eq!(from, MeshLevel::X40);
-                assert_eq!(to, MeshLevel::Lv1);
+                assert_eq!(from, MeshLevel::Lv3);
+                assert_eq!(to, MeshLevel::X2_5);
             }
             _ => panic!("Expected UnsupportedMeshLevelConversion error"),
         }
     }
 
+    #[test]
+    fn test_meshcode_to_lv3() {
+        let meshcode = MeshCode::try_from(53393599212).unwrap();
+        assert_eq!(meshcode.level, MeshLevel::Lv6);
+        assert_eq!(meshcode.to_lv3().unwrap(), 53393599);
+        assert_eq!(
+            MeshCode::try_from(5339359921).unwrap().to_lv3().unwrap(),
+            53393599
+        );
+        let meshcode = MeshCode::try_from(53393599).unwrap();
+        assert_eq!(meshcode.to_lv3().unwrap(), meshcode);
+
+        // Coarser codes span many Lv3 meshes
+        let meshcode = MeshCode::try_from(5339).unwrap();
+        assert_eq!(
+            meshcode.to_lv3(),
+            Err(JismeshError::InvalidMeshLevelForLowerLevel(
+                MeshLevel::Lv1,
+                MeshLevel::Lv3
+            ))
+        );
+        let meshcode = MeshCode::try_from(5339467).unwrap();
+        assert!(meshcode.to_lv3().is_err());
+    }
+
+    #[test]
+    fn test_meshcode_clamp_to_level() {
+        let meshcode = MeshCode::try_from(53393599212).unwrap();
+
+        // Coarser levels coarsen
+        assert_eq!(meshcode.clamp_to_level(MeshLevel::Lv3).unwrap(), 53393599);
+        assert_eq!(meshcode.clamp_to_level(MeshLevel::Lv1).unwrap(), 5339);
+        assert_eq!(meshcode.clamp_to_level(MeshLevel::X2).unwrap(), 533935885);
+        assert_eq!(meshcode.clamp_to_level(MeshLevel::X40).unwrap(), 53392);
+
+        // Equal and finer levels return the code unchanged
+        let meshcode = MeshCode::try_from(53393599).unwrap();
+        assert_eq!(meshcode.clamp_to_level(MeshLevel::Lv3).unwrap(), meshcode);
+        assert_eq!(meshcode.clamp_to_level(MeshLevel::Lv6).unwrap(), meshcode);
+        assert_eq!(meshcode.clamp_to_level(MeshLevel::X2).unwrap(), 533935885);
+        assert_eq!(meshcode.clamp_to_level(MeshLevel::Lv2).unwrap(), 533935);
+
+        // The longitude digits of 538000 are out of range, so neither its X40 parent nor its
+        // center can be calculated
+        let meshcode = MeshCode::try_from(538000).unwrap();
+        assert!(meshcode.clamp_to_level(MeshLevel::X40).is_err());
+        assert_eq!(meshcode.clamp_to_level(MeshLevel::Lv1).unwrap(), 5380);
+    }
+
+    #[test]
+    fn test_meshcode_parent() {
+        let cases = vec![
+            // (child, parent)
+            (53393599212, Some(5339359921)),
+            (53393599, Some(533935)),
+            (533935, Some(5339)),
+            (533935446, Some(5339354)),
+            (5339354, Some(533935)),
+            (533947637, Some(5339476)),
+            (5339235, Some(53392)),
+            (5339, None),
+        ];
+        for (child, expected) in cases {
+            let parent = MeshCode::try_from(child).unwrap().parent().unwrap();
+            assert_eq!(parent.map(|p| p.value), expected, "Failed for {}", child);
+        }
+    }
+
     #[test]
     fn test_meshcode_clone_and_copy() {
         let meshcode = MeshCode {
             value: 5339,
             level: MeshLevel::Lv1,
         };
+        #[allow(clippy::clone_on_copy)]
         let cloned = meshcode.clone();
         let copied = meshcode;
 
@@ -569,6 +1517,16 @@ mod tests {
         assert_ne!(meshcode1, meshcode4);
     }
 
+    #[test]
+    fn test_meshcode_u64_equality() {
+        let meshcode = MeshCode::try_from(5339).unwrap();
+        assert!(meshcode == 5339u64);
+        assert!(5339u64 == meshcode);
+        assert!(meshcode != 5340u64);
+        assert!(5340u64 != meshcode);
+        assert_eq!(meshcode == 5339u64, 5339u64 == meshcode);
+    }
+
     #[test]
     fn test_meshcode_point() {
         // Test cases mirroring the Python test data
@@ -601,6 +1559,283 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_meshcode_point_checked() {
+        let meshcode = MeshCode::try_from(53393599).unwrap();
+        for (lat_multiplier, lon_multiplier) in [(0.0, 0.0), (0.5, 0.5), (1.0, 1.0)] {
+            assert_eq!(
+                meshcode.point_checked(lat_multiplier, lon_multiplier),
+                meshcode.point(lat_multiplier, lon_multiplier)
+            );
+        }
+        for (lat_multiplier, lon_multiplier) in [(1.5, 0.5), (0.5, -0.1), (f64::NAN, 0.5)] {
+            assert!(matches!(
+                meshcode.point_checked(lat_multiplier, lon_multiplier),
+                Err(JismeshError::MultiplierOutOfRange(..))
+            ));
+        }
+        // The unchecked version still allows offsetting outside the mesh
+        assert!(meshcode.point(1.5, 0.5).is_ok());
+    }
+
+    #[test]
+    fn test_meshcode_bounds() {
+        let meshcode = MeshCode::try_from(53393599).unwrap();
+        let (lat_s, lon_w, lat_n, lon_e) = meshcode.bounds().unwrap();
+        assert_eq!((lat_s, lon_w), meshcode.point(0.0, 0.0).unwrap());
+        assert_eq!((lat_n, lon_e), meshcode.point(1.0, 1.0).unwrap());
+    }
+
+    #[test]
+    fn test_meshcode_corner() {
+        for value in [53393599, 5339, 5339467, 53393599212] {
+            let meshcode = MeshCode::try_from(value).unwrap();
+            let (lat_s, lon_w, lat_n, lon_e) = meshcode.bounds().unwrap();
+            let ((lat_c, lon_c), _) = meshcode.centroid_with_halfspan().unwrap();
+            for (corner, (lat, lon)) in [
+                (Corner::SW, (lat_s, lon_w)),
+                (Corner::SE, (lat_s, lon_e)),
+                (Corner::NW, (lat_n, lon_w)),
+                (Corner::NE, (lat_n, lon_e)),
+                (Corner::Center, (lat_c, lon_c)),
+            ] {
+                let (corner_lat, corner_lon) = meshcode.corner(corner).unwrap();
+                assert_relative_eq!(corner_lat, lat, epsilon = 1e-9);
+                assert_relative_eq!(corner_lon, lon, epsilon = 1e-9);
+            }
+        }
+        assert_eq!(Corner::iter().count(), 5);
+    }
+
+    #[test]
+    fn test_meshcode_center() {
+        let meshcode = MeshCode::try_from(53393599).unwrap();
+        let (lat, lon) = meshcode.center().unwrap();
+        assert_relative_eq!(lat, 35.6625, epsilon = 1e-9);
+        assert_relative_eq!(lon, 139.74375, epsilon = 1e-9);
+
+        // The center is the middle of the bounds
+        let (lat_s, lon_w, lat_n, lon_e) = meshcode.bounds().unwrap();
+        assert_relative_eq!(lat, (lat_s + lat_n) / 2.0, epsilon = 1e-9);
+        assert_relative_eq!(lon, (lon_w + lon_e) / 2.0, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn test_meshcode_centroid_with_halfspan() {
+        for value in [53393599, 5339, 533935446, 5339359921] {
+            let meshcode = MeshCode::try_from(value).unwrap();
+            let ((lat, lon), (half_lat, half_lon)) = meshcode.centroid_with_halfspan().unwrap();
+            let (lat_s, lon_w, lat_n, lon_e) = meshcode.bounds().unwrap();
+            assert_relative_eq!(lat - half_lat, lat_s, epsilon = 1e-12);
+            assert_relative_eq!(lon - half_lon, lon_w, epsilon = 1e-12);
+            assert_relative_eq!(lat + half_lat, lat_n, epsilon = 1e-12);
+            assert_relative_eq!(lon + half_lon, lon_e, epsilon = 1e-12);
+        }
+
+        // A Lv3 mesh is 30" x 45"
+        let meshcode = MeshCode::try_from(53393599).unwrap();
+        let (_, (half_lat, half_lon)) = meshcode.centroid_with_halfspan().unwrap();
+        assert_relative_eq!(half_lat, 15.0 / 3600.0, epsilon = 1e-12);
+        assert_relative_eq!(half_lon, 22.5 / 3600.0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_meshcode_bounding_circle_m() {
+        for value in [5339, 533935, 53393599, 53393599212] {
+            let meshcode = MeshCode::try_from(value).unwrap();
+            let ((lat, lon), radius) = meshcode.bounding_circle_m().unwrap();
+            assert_eq!((lat, lon), meshcode.point(0.5, 0.5).unwrap());
+            assert!(radius > 0.0);
+
+            for (lat_multiplier, lon_multiplier) in [(0.0, 0.0), (0.0, 1.0), (1.0, 0.0), (1.0, 1.0)]
+            {
+                let (corner_lat, corner_lon) =
+                    meshcode.point(lat_multiplier, lon_multiplier).unwrap();
+                let distance = geodesy::haversine_distance(lat, lon, corner_lat, corner_lon);
+                assert!(distance <= radius, "Failed for meshcode {}", value);
+            }
+        }
+
+        // A Lv3 mesh is about 1km square, so the radius is about 0.7km
+        let meshcode = MeshCode::try_from(53393599).unwrap();
+        let (_, radius) = meshcode.bounding_circle_m().unwrap();
+        assert!((600.0..750.0).contains(&radius), "radius: {}", radius);
+    }
+
+    #[test]
+    fn test_meshcode_lerp_centroid() {
+        let tokyo = MeshCode::try_from(53393599).unwrap();
+        let kyoto = MeshCode::try_from(52353680).unwrap();
+
+        assert_eq!(
+            tokyo.lerp_centroid(&kyoto, 0.0).unwrap(),
+            tokyo.point(0.5, 0.5).unwrap()
+        );
+        let (lat, lon) = tokyo.lerp_centroid(&kyoto, 1.0).unwrap();
+        let (expected_lat, expected_lon) = kyoto.point(0.5, 0.5).unwrap();
+        assert_relative_eq!(lat, expected_lat, epsilon = 1e-12);
+        assert_relative_eq!(lon, expected_lon, epsilon = 1e-12);
+
+        let (lat, lon) = tokyo.lerp_centroid(&kyoto, 0.5).unwrap();
+        assert_relative_eq!(lat, (35.6625 + 34.9875) / 2.0, epsilon = 1e-12);
+        assert_relative_eq!(lon, (139.74375 + 135.75625) / 2.0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn test_meshcode_label_point() {
+        let meshcode = MeshCode::try_from(53393599).unwrap();
+        assert_eq!(meshcode.label_point(), meshcode.point(0.5, 0.5));
+        assert_eq!(meshcode.label_point_jittered(0.0), meshcode.point(0.5, 0.5));
+
+        // The same code always gives the same point, inside the jitter box
+        let a = meshcode.label_point_jittered(0.25).unwrap();
+        let b = MeshCode::try_from(53393599)
+            .unwrap()
+            .label_point_jittered(0.25)
+            .unwrap();
+        assert_eq!(a, b);
+        let (lat, lon) = meshcode.fractional_position(a.0, a.1).unwrap();
+        assert!((0.25..=0.75).contains(&lat));
+        assert!((0.25..=0.75).contains(&lon));
+
+        // Neighbouring codes are scattered differently
+        let (lat2, lon2) = meshcode
+            .step(0, 1)
+            .unwrap()
+            .label_point_jittered(0.25)
+            .unwrap();
+        let neighbour = meshcode.step(0, 1).unwrap();
+        assert_ne!(
+            neighbour.fractional_position(lat2, lon2).unwrap(),
+            (lat, lon)
+        );
+
+        assert_eq!(
+            meshcode.label_point_jittered(0.6),
+            Err(JismeshError::JitterOutOfRange {
+                value: 0.6,
+                min: 0.0,
+                max: 0.5,
+            })
+        );
+        assert!(meshcode.label_point_jittered(-0.1).is_err());
+    }
+
+    #[test]
+    fn test_meshcode_is_within_japan() {
+        assert!(MeshCode::try_from(5339).unwrap().is_within_japan());
+        assert!(MeshCode::try_from(53393599).unwrap().is_within_japan());
+
+        // "0039" loses its leading zero as a u64, so build it directly
+        let far_south = MeshCode {
+            value: 39,
+            level: MeshLevel::Lv1,
+        };
+        assert!(!far_south.is_within_japan());
+        let far_north = MeshCode::try_from(9939).unwrap();
+        assert!(!far_north.is_within_japan());
+    }
+
+    #[test]
+    fn test_meshcode_contains_point() {
+        let meshcode = MeshCode::try_from(53393599).unwrap();
+        let (lat_s, lon_w, lat_n, lon_e) = meshcode.bounds().unwrap();
+        let d = 1e-9;
+
+        // Corners: only the SW corner is inside
+        assert!(meshcode.contains_point(lat_s, lon_w).unwrap());
+        assert!(!meshcode.contains_point(lat_s, lon_e).unwrap());
+        assert!(!meshcode.contains_point(lat_n, lon_w).unwrap());
+        assert!(!meshcode.contains_point(lat_n, lon_e).unwrap());
+
+        // Just inside and just outside each edge
+        let (lat_c, lon_c) = meshcode.center().unwrap();
+        for (lat, lon, inside) in [
+            (lat_s + d, lon_c, true),
+            (lat_s - d, lon_c, false),
+            (lat_n - d, lon_c, true),
+            (lat_n, lon_c, false),
+            (lat_c, lon_w + d, true),
+            (lat_c, lon_w - d, false),
+            (lat_c, lon_e - d, true),
+            (lat_c, lon_e, false),
+        ] {
+            assert_eq!(
+                meshcode.contains_point(lat, lon).unwrap(),
+                inside,
+                "Failed for ({}, {})",
+                lat,
+                lon
+            );
+        }
+
+        // A point on a shared edge belongs to exactly one of the neighbors
+        let east = meshcode.neighbor(Direction::E).unwrap();
+        assert!(east.contains_point(lat_c, lon_e).unwrap());
+    }
+
+    #[test]
+    fn test_meshcode_child_at_point() {
+        let lat = 35.658581;
+        let lon = 139.745433;
+        let meshcode = MeshCode::try_from(5339).unwrap();
+
+        let child = meshcode.child_at_point(lat, lon, MeshLevel::Lv3).unwrap();
+        assert_eq!(child, 53393599);
+        assert_eq!(child.level, MeshLevel::Lv3);
+
+        let child = meshcode.child_at_point(lat, lon, MeshLevel::Lv1).unwrap();
+        assert_eq!(child, meshcode);
+    }
+
+    #[test]
+    fn test_meshcode_child_at_point_errors() {
+        let meshcode = MeshCode::try_from(5339).unwrap();
+
+        // Kyoto is not inside 5339
+        assert_eq!(
+            meshcode.child_at_point(34.987574, 135.759363, MeshLevel::Lv3),
+            Err(JismeshError::PointOutsideMeshCode(
+                34.987574, 135.759363, 5339
+            ))
+        );
+
+        let meshcode = MeshCode::try_from(533935).unwrap();
+        assert_eq!(
+            meshcode.child_at_point(35.658581, 139.745433, MeshLevel::Lv1),
+            Err(JismeshError::InvalidMeshLevelForHigherLevel(
+                MeshLevel::Lv1,
+                MeshLevel::Lv2
+            ))
+        );
+    }
+
+    #[test]
+    fn test_meshcode_fractional_position() {
+        let meshcode = MeshCode::try_from(53393599).unwrap();
+        let (u, v) = meshcode.fractional_position(35.658581, 139.745433).unwrap();
+        assert!((0.0..=1.0).contains(&u));
+        assert!((0.0..=1.0).contains(&v));
+        let (lat, lon) = meshcode.point(u, v).unwrap();
+        assert_relative_eq!(lat, 35.658581, epsilon = 1e-9);
+        assert_relative_eq!(lon, 139.745433, epsilon = 1e-9);
+
+        // Corners and center
+        for (u, v) in [(0.0, 0.0), (1.0, 1.0), (0.5, 0.5), (0.25, 0.75)] {
+            let (lat, lon) = meshcode.point(u, v).unwrap();
+            let (u2, v2) = meshcode.fractional_position(lat, lon).unwrap();
+            assert_relative_eq!(u, u2, epsilon = 1e-9);
+            assert_relative_eq!(v, v2, epsilon = 1e-9);
+        }
+
+        assert_eq!(
+            meshcode.fractional_position(35.0, 139.745433),
+            Err(JismeshError::PointOutsideMeshCode(
+                35.0, 139.745433, 53393599
+            ))
+        );
+    }
+
     #[test]
     fn test_meshcode_contains() {
         let cases = vec![
@@ -624,15 +1859,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_meshcode_contains_extended() {
+        let cases = vec![
+            // (parent, child, expected)
+            (53392, 533935, true),   // X40 contains a Lv2 inside its area
+            (53392, 53393599, true), // X40 contains a Lv3 inside its area
+            (53392, 53393599212, true),
+            (53392, 53394500, false),     // Lv3 in the north half of 5339
+            (5339467, 53393599, true),    // X16 contains a Lv3
+            (5339, 533935446, true),      // Lv1 contains an X2.5
+            (533935, 5339354, true),      // Lv2 contains an X5
+            (5339354, 533935, false),     // X5 doesn't contain its Lv2
+            (533935446, 53393599, true),  // X2.5 contains a Lv3
+            (533935446, 53393577, false), // Lv3 straddling the X2.5 boundary
+        ];
+        for (parent_value, child_value, expected) in cases {
+            let parent = MeshCode::try_from(parent_value).unwrap();
+            let child = MeshCode::try_from(child_value).unwrap();
+            assert_eq!(
+                parent.contains(&child),
+                expected,
+                "Failed for parent {} and child {}",
+                parent_value,
+                child_value
+            );
+        }
+    }
+
     #[test]
     fn test_meshcode_intersects() {
         let cases = vec![
             // (left, right, expected)
-            (5339, 5339, true),    // Same level
-            (5339, 533911, true),  // right at higher level
-            (533900, 5339, true),  // right at lower level
-            (5339, 5340, false),   // Same level, disjoint
-            (5339, 534001, false), // right at higher level, disjoint
+            (5339, 5339, true),           // Same level
+            (5339, 533911, true),         // right at higher level
+            (533900, 5339, true),         // right at lower level
+            (5339, 5340, false),          // Same level, disjoint
+            (5339, 534001, false),        // right at higher level, disjoint
+            (53392, 53393599, true),      // X40 and a Lv3 inside it
+            (533935446, 53393577, true),  // Partially overlapping X2.5 and Lv3
+            (533935446, 53394509, false), // Lv3 touching the X2.5 from the north
         ];
         for (left_value, right_value, expected) in cases {
             let left = MeshCode::try_from(left_value).unwrap();
@@ -646,4 +1912,436 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_meshcode_to_formatted() {
+        let cases = [
+            (5339, "5339"),
+            (53392, "5339-2"),
+            (5339235, "5339-2-35"),
+            (5339467, "5339-467"),
+            (533935, "5339-35"),
+            (5339476, "5339-476"),
+            (5339354, "5339-35-4"),
+            (533947637, "5339-476-37"),
+            (533935446, "5339-35-4-46"),
+            (533935885, "5339-35-885"),
+            (53393599, "5339-35-99"),
+            (533935992, "5339-35-99-2"),
+            (5339359921, "5339-35-99-2-1"),
+            (53393599212, "5339-35-99-2-1-2"),
+        ];
+        for (value, formatted) in cases {
+            let meshcode = MeshCode::try_from(value).unwrap();
+            assert_eq!(meshcode.to_formatted(), formatted);
+            assert_eq!(MeshCode::from_formatted(formatted), Ok(meshcode));
+            // Display stays the bare number
+            assert_eq!(meshcode.to_string(), value.to_string());
+        }
+    }
+
+    #[test]
+    fn test_meshcode_from_formatted() {
+        let meshcode = MeshCode::try_from(53393599).unwrap();
+        assert_eq!(MeshCode::from_formatted("5339 35 99"), Ok(meshcode));
+        assert_eq!(MeshCode::from_formatted(" 5339-35 99 "), Ok(meshcode));
+        assert_eq!(MeshCode::from_formatted("53393599"), Ok(meshcode));
+
+        for s in [
+            "533-935-99",
+            "5339-3599",
+            "5339--35-99",
+            "5339-35-99-",
+            "5339-35-9a",
+            "5339-35-99-22",
+            "",
+        ] {
+            assert_eq!(
+                MeshCode::from_formatted(s),
+                Err(JismeshError::InvalidMeshCode(s.to_string())),
+                "Failed for {:?}",
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn test_meshcode_stable_hash() {
+        let meshcode = MeshCode::try_from(53393599).unwrap();
+        // The hash is pinned so that it never changes between runs or versions
+        assert_eq!(meshcode.stable_hash(), 0xba39_83a3_b75c_c376);
+        assert_eq!(
+            meshcode.stable_hash(),
+            MeshCode::try_from(53393599).unwrap().stable_hash()
+        );
+
+        // Different codes hash differently
+        for value in [53393598, 53393589, 533935, 5339476, 533935992] {
+            let other = MeshCode::try_from(value).unwrap();
+            assert_ne!(meshcode.stable_hash(), other.stable_hash());
+        }
+    }
+
+    /// Checks that every X16 / X2 mesh code inside `parent` is encoded with even digits
+    /// and survives a round trip through points inside the mesh.
+    fn check_even_digit_round_trip(parent: u64, level: MeshLevel) {
+        let parent = MeshCode::try_from(parent).unwrap();
+        for row in 0..5 {
+            for col in 0..5 {
+                let lat = (row as f64 + 0.5) / 5.0;
+                let lon = (col as f64 + 0.5) / 5.0;
+                let (lat, lon) = parent.point(lat, lon).unwrap();
+                let code = MeshCode::try_from_latlng(lat, lon, level).unwrap();
+                let expected = parent.value * 1000 + row * 2 * 100 + col * 2 * 10 + code.value % 10;
+                assert_eq!(code.value, expected);
+                assert_eq!(MeshCode::try_from(code.value).unwrap(), code);
+
+                for (lat_multiplier, lon_multiplier) in [(0.5, 0.5), (0.01, 0.01), (0.99, 0.99)] {
+                    let (lat, lon) = code.point(lat_multiplier, lon_multiplier).unwrap();
+                    assert_eq!(
+                        MeshCode::try_from_latlng(lat, lon, level).unwrap(),
+                        code,
+                        "Failed for {} at ({}, {})",
+                        code,
+                        lat_multiplier,
+                        lon_multiplier
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_x16_round_trip() {
+        for lv1 in [5339, 5235, 6441, 3036, 6840, 4729] {
+            check_even_digit_round_trip(lv1, MeshLevel::X16);
+        }
+    }
+
+    #[test]
+    fn test_x2_round_trip() {
+        for lv2 in [533935, 523536, 644142, 303622, 684007, 472977] {
+            check_even_digit_round_trip(lv2, MeshLevel::X2);
+        }
+    }
+
+    #[test]
+    fn test_is_in_valid_range() {
+        // Lower bounds are inclusive
+        assert!(is_in_valid_range(0.0, 100.0));
+        // Upper bounds are exclusive
+        assert!(!is_in_valid_range(66.66, 139.0));
+        assert!(!is_in_valid_range(35.0, 180.0));
+        assert!(is_in_valid_range(66.659999, 179.999999));
+        // Outside the domain
+        assert!(!is_in_valid_range(-0.000001, 139.0));
+        assert!(!is_in_valid_range(35.0, 99.999999));
+        assert!(!is_in_valid_range(f64::NAN, 139.0));
+        assert!(!is_in_valid_range(35.0, f64::NAN));
+
+        // Agrees with to_meshcode
+        for (lat, lon) in [(0.0, 100.0), (66.66, 139.0), (35.0, 180.0), (35.0, 99.9)] {
+            assert_eq!(
+                is_in_valid_range(lat, lon),
+                to_meshcode(&[lat], &[lon], MeshLevel::Lv1).is_ok()
+            );
+        }
+    }
+
+    #[test]
+    fn test_to_meshcode_with_boundary_lv1() {
+        let convert = |lat: f64, lon: f64, boundary: BoundaryRule| -> u64 {
+            to_meshcode_with_boundary(&[lat], &[lon], MeshLevel::Lv1, boundary).unwrap()[0].into()
+        };
+
+        // Inside a mesh, both rules agree
+        assert_eq!(convert(35.5, 139.5, BoundaryRule::LowerInclusive), 5339);
+        assert_eq!(convert(35.5, 139.5, BoundaryRule::UpperInclusive), 5339);
+
+        // On the west edge of 5339 (lon exactly 139.0)
+        assert_eq!(convert(35.5, 139.0, BoundaryRule::LowerInclusive), 5339);
+        assert_eq!(convert(35.5, 139.0, BoundaryRule::UpperInclusive), 5338);
+
+        // On the south-west corner of 5439
+        let (lat_s, lon_w, _, _) = MeshCode::try_from(5439).unwrap().bounds().unwrap();
+        assert_eq!(convert(lat_s, lon_w, BoundaryRule::LowerInclusive), 5439);
+        assert_eq!(convert(lat_s, lon_w, BoundaryRule::UpperInclusive), 5338);
+
+        // The default rule matches to_meshcode
+        assert_eq!(BoundaryRule::default(), BoundaryRule::LowerInclusive);
+        assert_eq!(
+            to_meshcode_with_boundary(&[35.5], &[139.0], MeshLevel::Lv1, Default::default()),
+            to_meshcode(&[35.5], &[139.0], MeshLevel::Lv1)
+        );
+    }
+
+    #[test]
+    fn test_to_meshcode_with_boundary_lv3() {
+        let meshcode: MeshCode = 53393599.try_into().unwrap();
+        let (lat_s, lon_w, lat_n, lon_e) = meshcode.bounds().unwrap();
+        let lat_mid = (lat_s + lat_n) / 2.0;
+        let lon_mid = (lon_w + lon_e) / 2.0;
+        let lower = |lat: f64, lon: f64| {
+            to_meshcode_with_boundary(&[lat], &[lon], MeshLevel::Lv3, BoundaryRule::LowerInclusive)
+                .unwrap()[0]
+        };
+        let upper = |lat: f64, lon: f64| {
+            to_meshcode_with_boundary(&[lat], &[lon], MeshLevel::Lv3, BoundaryRule::UpperInclusive)
+                .unwrap()[0]
+        };
+
+        // South edge
+        assert_eq!(lower(lat_s, lon_mid), meshcode);
+        assert_eq!(upper(lat_s, lon_mid), meshcode.step(-1, 0).unwrap());
+        // West edge
+        assert_eq!(lower(lat_mid, lon_w), meshcode);
+        assert_eq!(upper(lat_mid, lon_w), meshcode.step(0, -1).unwrap());
+        // South-west corner
+        assert_eq!(lower(lat_s, lon_w), meshcode);
+        assert_eq!(upper(lat_s, lon_w), meshcode.step(-1, -1).unwrap());
+        // North-east corner
+        assert_eq!(upper(lat_n, lon_e), meshcode);
+    }
+
+    #[test]
+    fn test_to_meshcode_with_boundary_domain_edge() {
+        // Points on the south and west edges of the domain have no mesh below them
+        assert_eq!(
+            to_meshcode_with_boundary(
+                &[0.0],
+                &[139.5],
+                MeshLevel::Lv3,
+                BoundaryRule::UpperInclusive
+            ),
+            Err(JismeshError::LatitudeOutOfBounds {
+                value: 0.0,
+                index: 0,
+            })
+        );
+        assert_eq!(
+            to_meshcode_with_boundary(
+                &[35.5],
+                &[100.0],
+                MeshLevel::Lv3,
+                BoundaryRule::UpperInclusive
+            ),
+            Err(JismeshError::LongitudeOutOfBounds {
+                value: 100.0,
+                index: 0,
+            })
+        );
+        assert!(
+            to_meshcode_with_boundary(
+                &[0.0],
+                &[100.0],
+                MeshLevel::Lv3,
+                BoundaryRule::LowerInclusive
+            )
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_to_meshcode_with_boundary_empty_input() {
+        for boundary in [BoundaryRule::LowerInclusive, BoundaryRule::UpperInclusive] {
+            assert_eq!(
+                to_meshcode_with_boundary(&[], &[139.5], MeshLevel::Lv3, boundary),
+                Err(JismeshError::EmptyInput)
+            );
+            assert_eq!(
+                to_meshcode_with_boundary(&[35.5], &[], MeshLevel::Lv3, boundary),
+                Err(JismeshError::EmptyInput)
+            );
+            assert_eq!(
+                to_meshcode_with_boundary(&[], &[], MeshLevel::Lv3, boundary),
+                Ok(vec![])
+            );
+        }
+    }
+
+    #[test]
+    fn test_area_m2() {
+        // Lv3 mesh near Tokyo station, checked against a numerical integration of the
+        // WGS84 area element (1,046,281.3 m²)
+        let meshcode: MeshCode = 53394611.try_into().unwrap();
+        assert_relative_eq!(
+            meshcode.area_m2().unwrap(),
+            1_046_281.3,
+            max_relative = 1e-3
+        );
+
+        // Children add up to the parent
+        let total: f64 = meshcode
+            .children(MeshLevel::Lv6)
+            .unwrap()
+            .iter()
+            .map(|child| child.area_m2().unwrap())
+            .sum();
+        assert_relative_eq!(total, meshcode.area_m2().unwrap(), max_relative = 1e-9);
+
+        // Meshes further north are smaller
+        let south: MeshCode = 3036.try_into().unwrap();
+        let north: MeshCode = 6841.try_into().unwrap();
+        assert!(south.area_m2().unwrap() > north.area_m2().unwrap());
+    }
+
+    #[test]
+    fn test_distance_to() {
+        let meshcode: MeshCode = 53394611.try_into().unwrap();
+        assert_eq!(meshcode.distance_to(&meshcode).unwrap(), 0.0);
+
+        // Adjacent Lv3 meshes: 45" of longitude and 30" of latitude apart
+        let east: MeshCode = 53394612.try_into().unwrap();
+        let north: MeshCode = 53394621.try_into().unwrap();
+        assert_relative_eq!(
+            meshcode.distance_to(&east).unwrap(),
+            1_129.0,
+            max_relative = 1e-3
+        );
+        assert_relative_eq!(
+            meshcode.distance_to(&north).unwrap(),
+            926.6,
+            max_relative = 1e-3
+        );
+        assert_eq!(
+            meshcode.distance_to(&east).unwrap(),
+            east.distance_to(&meshcode).unwrap()
+        );
+
+        // Distant Lv1 meshes: Tokyo (5339) to Sapporo (6441), about 833km
+        let tokyo: MeshCode = 5339.try_into().unwrap();
+        let sapporo: MeshCode = 6441.try_into().unwrap();
+        assert_relative_eq!(
+            tokyo.distance_to(&sapporo).unwrap(),
+            833_302.0,
+            max_relative = 1e-3
+        );
+
+        // Meshes of different levels are compared by their centers
+        assert!(tokyo.distance_to(&meshcode).unwrap() > 0.0);
+    }
+
+    #[test]
+    fn test_from_str_whitespace() {
+        let expected: MeshCode = 53393599.try_into().unwrap();
+        for input in ["53393599", " 53393599 ", "\t53393599\n", "  53393599"] {
+            assert_eq!(input.parse::<MeshCode>().unwrap(), expected, "{:?}", input);
+            assert_eq!(
+                MeshCode::from_str_exact(input).unwrap(),
+                expected,
+                "{:?}",
+                input
+            );
+        }
+        assert!(" ".parse::<MeshCode>().is_err());
+        assert!("5339 3599".parse::<MeshCode>().is_err());
+    }
+
+    #[test]
+    fn test_from_str_exact_leading_zeros() {
+        // from_str drops the leading zero and reads a 9 digit string as a Lv3 code
+        let lenient: MeshCode = "053393599".parse().unwrap();
+        assert_eq!(lenient.level, MeshLevel::Lv3);
+
+        // from_str_exact refuses to change the number of digits
+        assert_eq!(
+            MeshCode::from_str_exact("053393599"),
+            Err(JismeshError::InvalidMeshCode("053393599".to_string()))
+        );
+        assert!(MeshCode::from_str_exact(" 0939 ").is_err());
+
+        // Signs and other characters are rejected too
+        assert!(MeshCode::from_str_exact("+53393599").is_err());
+        assert!(MeshCode::from_str_exact("5339-35-99").is_err());
+        assert!(MeshCode::from_str_exact("").is_err());
+        assert!(MeshCode::from_str_exact("0").is_err());
+    }
+
+    #[test]
+    fn test_digits() {
+        let meshcode: MeshCode = 53393599.try_into().unwrap();
+        assert_eq!(meshcode.digits(), vec![5, 3, 3, 9, 3, 5, 9, 9]);
+        assert_eq!(meshcode.digit_len(), 8);
+
+        for value in [
+            5339,
+            53392,
+            5339235,
+            5339467,
+            533935,
+            5339354,
+            533947637,
+            533935446,
+            533935885,
+            53393599,
+            533935992,
+            5339359921,
+            53393599211,
+            10000,
+        ] {
+            let meshcode = MeshCode::try_from(value).unwrap();
+            let digits = meshcode.digits();
+            assert_eq!(digits.len(), meshcode.digit_len() as usize, "{}", value);
+            let joined = digits.iter().fold(0_u64, |acc, &d| acc * 10 + d as u64);
+            assert_eq!(joined, value, "{}", value);
+        }
+
+        // A parent's digits are a prefix of its children's digits
+        let child: MeshCode = 53393599211.try_into().unwrap();
+        let parent = child.to_lv3().unwrap();
+        assert!(child.digits().starts_with(&parent.digits()));
+    }
+
+    #[test]
+    fn test_to_meshcode_empty_input() {
+        assert_eq!(to_meshcode(&[], &[], MeshLevel::Lv3), Ok(vec![]));
+        assert_eq!(
+            to_meshcode(&[], &[139.7], MeshLevel::Lv3),
+            Err(JismeshError::EmptyInput)
+        );
+        assert_eq!(
+            to_meshcode(&[35.6], &[], MeshLevel::Lv3),
+            Err(JismeshError::EmptyInput)
+        );
+    }
+
+    #[test]
+    fn test_out_of_bounds_index() {
+        assert_eq!(
+            to_meshcode(&[35.0, 36.0, 70.0, 80.0], &[139.0], MeshLevel::Lv3),
+            Err(JismeshError::LatitudeOutOfBounds {
+                value: 70.0,
+                index: 2,
+            })
+        );
+        assert_eq!(
+            to_meshcode(&[35.0], &[139.0, 190.0], MeshLevel::Lv3),
+            Err(JismeshError::LongitudeOutOfBounds {
+                value: 190.0,
+                index: 1,
+            })
+        );
+        assert_eq!(
+            JismeshError::LatitudeOutOfBounds {
+                value: 70.0,
+                index: 2,
+            }
+            .to_string(),
+            "Latitude 70 at index 2 is out of bounds of the Japanese mesh system (0 <= lat < 66.66)"
+        );
+
+        // Broadcast inputs report the index in their own slice
+        assert_eq!(
+            to_meshcode_with_boundary(
+                &[35.5],
+                &[139.5, 100.0],
+                MeshLevel::Lv3,
+                BoundaryRule::UpperInclusive
+            ),
+            Err(JismeshError::LongitudeOutOfBounds {
+                value: 100.0,
+                index: 1,
+            })
+        );
+    }
 }
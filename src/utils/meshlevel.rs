@@ -8,57 +8,73 @@ pub fn to_meshlevel(meshcode: &[u64]) -> Result<Vec<MeshLevel>> {
         return Err(JismeshError::UnknownMeshLevelForCode(0));
     }
 
-    // Calculate number of digits for each meshcode
-    let num_digits: Vec<usize> = meshcode
-        .iter()
-        .map(|&code| (code as f64).log10().floor() as usize + 1)
-        .collect();
-
-    // Extract the g and i digits needed for determining mesh levels
-    let g = slice(meshcode, 6, 7);
-    let i = slice(meshcode, 8, 9);
-    let j = slice(meshcode, 9, 10);
-    let k = slice(meshcode, 10, 11);
-
-    // Create a result vector to store mesh levels
-    let mut results = Vec::with_capacity(meshcode.len());
-
-    // Determine mesh level for each meshcode
-    for idx in 0..meshcode.len() {
-        let level = match num_digits[idx] {
-            4 => MeshLevel::Lv1,
-            5 => MeshLevel::X40,
-            6 => MeshLevel::Lv2,
-            7 => match g[idx] {
-                1..=4 => MeshLevel::X5,
-                5 => MeshLevel::X20,
-                6 => MeshLevel::X8,
-                7 => MeshLevel::X16,
-                _ => return Err(JismeshError::InvalidMeshcodeAtLevel(7, meshcode[idx])),
-            },
-            8 => MeshLevel::Lv3,
-            9 => match i[idx] {
-                1..=4 => MeshLevel::Lv4,
-                5 => MeshLevel::X2,
-                6 => MeshLevel::X2_5,
-                7 => MeshLevel::X4,
-                _ => return Err(JismeshError::InvalidMeshcodeAtLevel(9, meshcode[idx])),
-            },
-            10 => match j[idx] {
-                1..=4 => MeshLevel::Lv5,
-                _ => return Err(JismeshError::InvalidMeshcodeAtLevel(10, meshcode[idx])),
-            },
-            11 => match k[idx] {
-                1..=4 => MeshLevel::Lv6,
-                _ => return Err(JismeshError::InvalidMeshcodeAtLevel(11, meshcode[idx])),
-            },
-            _ => return Err(JismeshError::UnknownMeshLevelForCode(meshcode[idx])),
-        };
+    meshcode.iter().map(|&code| level_of(code)).collect()
+}
 
-        results.push(level);
+/// Determines the mesh level of a single meshcode.
+///
+/// Digits are counted and extracted with integer arithmetic only, so values at a power of
+/// ten (e.g. 10000) are never off by one.
+///
+/// # Arguments
+/// * `code` - Meshcode
+///
+/// # Returns
+/// * `Result<MeshLevel>` - Mesh level of the meshcode
+///
+/// # Errors
+/// * Returns an error if the number of digits or the level suffix is not a known level
+pub fn level_of(code: u64) -> Result<MeshLevel> {
+    if code == 0 {
+        return Err(JismeshError::UnknownMeshLevelForCode(0));
     }
+    let num_digits = num_digits(code);
+    // The digit at a 0-based position from the left
+    let digit = |pos: u32| (code / 10_u64.pow(num_digits - pos - 1) % 10) as u8;
+
+    let level = match num_digits {
+        4 => MeshLevel::Lv1,
+        5 => MeshLevel::X40,
+        6 => MeshLevel::Lv2,
+        7 => match digit(6) {
+            1..=4 => MeshLevel::X5,
+            5 => MeshLevel::X20,
+            6 => MeshLevel::X8,
+            // X16 only uses even digits (0, 2, 4, 6, 8) for e and f
+            7 if digit(4).is_multiple_of(2) && digit(5).is_multiple_of(2) => MeshLevel::X16,
+            _ => return Err(JismeshError::InvalidMeshcodeAtLevel(7, code)),
+        },
+        8 => MeshLevel::Lv3,
+        9 => match digit(8) {
+            1..=4 => MeshLevel::Lv4,
+            // X2 only uses even digits (0, 2, 4, 6, 8) for g and h
+            5 if digit(6).is_multiple_of(2) && digit(7).is_multiple_of(2) => MeshLevel::X2,
+            6 => MeshLevel::X2_5,
+            7 => MeshLevel::X4,
+            _ => return Err(JismeshError::InvalidMeshcodeAtLevel(9, code)),
+        },
+        10 => match digit(9) {
+            1..=4 => MeshLevel::Lv5,
+            _ => return Err(JismeshError::InvalidMeshcodeAtLevel(10, code)),
+        },
+        11 => match digit(10) {
+            1..=4 => MeshLevel::Lv6,
+            _ => return Err(JismeshError::InvalidMeshcodeAtLevel(11, code)),
+        },
+        _ => return Err(JismeshError::UnknownMeshLevelForCode(code)),
+    };
+    Ok(level)
+}
 
-    Ok(results)
+/// Determines the mesh level of each meshcode independently.
+///
+/// Unlike `to_meshlevel`, an invalid meshcode doesn't stop the whole batch, so all
+/// failures of a large column can be inspected at once.
+///
+/// # Returns
+/// * `Vec<Result<MeshLevel>>` - One result per input meshcode, in input order
+pub fn to_meshlevel_lenient(meshcode: &[u64]) -> Vec<Result<MeshLevel>> {
+    meshcode.iter().map(|&code| level_of(code)).collect()
 }
 
 #[cfg(test)]
@@ -107,9 +123,156 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_meshlevel_lenient() {
+        let results = to_meshlevel_lenient(&[5339, 0, 53393599, 5339317, 533935]);
+        assert_eq!(
+            results,
+            vec![
+                Ok(MeshLevel::Lv1),
+                Err(JismeshError::UnknownMeshLevelForCode(0)),
+                Ok(MeshLevel::Lv3),
+                Err(JismeshError::InvalidMeshcodeAtLevel(7, 5339317)),
+                Ok(MeshLevel::Lv2),
+            ]
+        );
+        assert!(to_meshlevel_lenient(&[]).is_empty());
+
+        // A 12 digit code fails on its own, without affecting the others
+        let results = to_meshlevel_lenient(&[533935992341, 5339, 0, 53393599]);
+        assert_eq!(
+            results,
+            vec![
+                Err(JismeshError::UnknownMeshLevelForCode(533935992341)),
+                Ok(MeshLevel::Lv1),
+                Err(JismeshError::UnknownMeshLevelForCode(0)),
+                Ok(MeshLevel::Lv3),
+            ]
+        );
+    }
+
     #[test]
     fn test_meshlevel_invalid() {
         let res = to_meshlevel(&[5]);
         assert!(res.is_err());
     }
+
+    #[test]
+    fn test_meshlevel_power_of_ten() {
+        // 5 digits, detected as X40
+        assert_eq!(to_meshlevel(&[10000]), Ok(vec![MeshLevel::X40]));
+        // 4 digits, one less than a power of ten
+        assert_eq!(to_meshlevel(&[9999]), Ok(vec![MeshLevel::Lv1]));
+        // 9 digits, the 9th digit 0 is not a valid level suffix
+        assert_eq!(
+            to_meshlevel(&[100000000]),
+            Err(JismeshError::InvalidMeshcodeAtLevel(9, 100000000))
+        );
+        // 12 digits is not a known level
+        assert_eq!(
+            to_meshlevel(&[100000000000]),
+            Err(JismeshError::UnknownMeshLevelForCode(100000000000))
+        );
+    }
+
+    #[test]
+    fn test_meshlevel_invalid_odd_digits() {
+        // X16 and X2 codes with odd digits would decode to the same point as the
+        // even code below them, so they are rejected
+        for meshcode in [5339317, 5339387, 5339237] {
+            assert_eq!(
+                to_meshlevel(&[meshcode]),
+                Err(JismeshError::InvalidMeshcodeAtLevel(7, meshcode))
+            );
+        }
+        for meshcode in [533935315, 533935135, 533935875] {
+            assert_eq!(
+                to_meshlevel(&[meshcode]),
+                Err(JismeshError::InvalidMeshcodeAtLevel(9, meshcode))
+            );
+        }
+    }
+
+    #[test]
+    fn test_level_of_digit_boundaries() {
+        let cases = vec![
+            (999, Err(JismeshError::UnknownMeshLevelForCode(999))),
+            (1000, Ok(MeshLevel::Lv1)),
+            (9999, Ok(MeshLevel::Lv1)),
+            (10000, Ok(MeshLevel::X40)),
+            (99999, Ok(MeshLevel::X40)),
+            (100000, Ok(MeshLevel::Lv2)),
+            (999999, Ok(MeshLevel::Lv2)),
+            (
+                1000000,
+                Err(JismeshError::InvalidMeshcodeAtLevel(7, 1000000)),
+            ),
+            (
+                9999999,
+                Err(JismeshError::InvalidMeshcodeAtLevel(7, 9999999)),
+            ),
+            (10000000, Ok(MeshLevel::Lv3)),
+            (99999999, Ok(MeshLevel::Lv3)),
+            (
+                100000000,
+                Err(JismeshError::InvalidMeshcodeAtLevel(9, 100000000)),
+            ),
+            (
+                999999999,
+                Err(JismeshError::InvalidMeshcodeAtLevel(9, 999999999)),
+            ),
+            (
+                1000000000,
+                Err(JismeshError::InvalidMeshcodeAtLevel(10, 1000000000)),
+            ),
+            (
+                9999999999,
+                Err(JismeshError::InvalidMeshcodeAtLevel(10, 9999999999)),
+            ),
+            (
+                10000000000,
+                Err(JismeshError::InvalidMeshcodeAtLevel(11, 10000000000)),
+            ),
+            (
+                99999999999,
+                Err(JismeshError::InvalidMeshcodeAtLevel(11, 99999999999)),
+            ),
+            (
+                100000000000,
+                Err(JismeshError::UnknownMeshLevelForCode(100000000000)),
+            ),
+            (
+                u64::MAX,
+                Err(JismeshError::UnknownMeshLevelForCode(u64::MAX)),
+            ),
+            (0, Err(JismeshError::UnknownMeshLevelForCode(0))),
+        ];
+        for (code, expected) in cases {
+            assert_eq!(level_of(code), expected, "{}", code);
+        }
+    }
+
+    #[test]
+    fn test_level_of_every_level() {
+        let cases = [
+            (5339, MeshLevel::Lv1),
+            (53392, MeshLevel::X40),
+            (5339235, MeshLevel::X20),
+            (5339467, MeshLevel::X16),
+            (533935, MeshLevel::Lv2),
+            (5339476, MeshLevel::X8),
+            (5339354, MeshLevel::X5),
+            (533947637, MeshLevel::X4),
+            (533935446, MeshLevel::X2_5),
+            (533935885, MeshLevel::X2),
+            (53393599, MeshLevel::Lv3),
+            (533935992, MeshLevel::Lv4),
+            (5339359921, MeshLevel::Lv5),
+            (53393599211, MeshLevel::Lv6),
+        ];
+        for (code, expected) in cases {
+            assert_eq!(level_of(code), Ok(expected), "{}", code);
+            assert_eq!(to_meshlevel(&[code]), Ok(vec![expected]), "{}", code);
+        }
+    }
 }
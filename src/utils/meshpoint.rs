@@ -11,7 +11,7 @@ fn apply_level_40000(idx: usize, e: &[u8], lat: &mut [f64], lon: &mut [f64]) {
     if e[idx] / 3 == 1 {
         lat[idx] += UNIT_LAT_40000;
     }
-    if e[idx] % 2 == 0 {
+    if e[idx].is_multiple_of(2) {
         lon[idx] += UNIT_LON_40000;
     }
 }
@@ -41,6 +41,7 @@ fn apply_level_3(
 }
 
 /// Applies the level 4 adjustment which builds on level 3
+#[allow(clippy::too_many_arguments)]
 fn apply_level_4(
     idx: usize,
     e: &[u8],
@@ -58,12 +59,13 @@ fn apply_level_4(
     if i[idx] / 3 == 1 {
         lat[idx] += UNIT_LAT_LV4;
     }
-    if i[idx] % 2 == 0 {
+    if i[idx].is_multiple_of(2) {
         lon[idx] += UNIT_LON_LV4;
     }
 }
 
 /// Applies the level 5 adjustment which builds on level 4
+#[allow(clippy::too_many_arguments)]
 fn apply_level_5(
     idx: usize,
     e: &[u8],
@@ -82,7 +84,7 @@ fn apply_level_5(
     if j[idx] / 3 == 1 {
         lat[idx] += UNIT_LAT_LV5;
     }
-    if j[idx] % 2 == 0 {
+    if j[idx].is_multiple_of(2) {
         lon[idx] += UNIT_LON_LV5;
     }
 }
@@ -109,6 +111,11 @@ pub fn to_meshpoint(
     // Convert single values to arrays
     let meshcode_len = meshcode.len();
 
+    // Multipliers are broadcast by repeating the last value, so at least one is required
+    if meshcode_len > 0 && (lat_multiplier.is_empty() || lon_multiplier.is_empty()) {
+        return Err(JismeshError::EmptyInput);
+    }
+
     // Get the mesh level for each code
     let level = to_meshlevel(meshcode)?;
 
@@ -132,6 +139,14 @@ pub fn to_meshpoint(
         // Start with level 1 coordinates (base for all mesh levels)
         apply_base_adjustment(idx, &ab, &cd, &mut lat, &mut lon);
 
+        // The encoder only accepts 100 <= lon < 180, so cd >= 80 can't be a valid meshcode
+        if lon[idx] >= 180.0 {
+            return Err(JismeshError::LongitudeOutOfBounds {
+                value: lon[idx],
+                index: idx,
+            });
+        }
+
         match level[idx] {
             // Level 1 - already handled in apply_base_adjustment
             MeshLevel::Lv1 => {}
@@ -150,13 +165,14 @@ pub fn to_meshpoint(
                 if f[idx] / 3 == 1 {
                     lat[idx] += UNIT_LAT_20000;
                 }
-                if f[idx] % 2 == 0 {
+                if f[idx].is_multiple_of(2) {
                     lon[idx] += UNIT_LON_20000;
                 }
             }
 
             // Level 16000
             MeshLevel::X16 => {
+                // e and f are even digits, see `MeshLevel::X16`
                 lat[idx] += (e[idx] / 2) as f64 * UNIT_LAT_16000;
                 lon[idx] += (f[idx] / 2) as f64 * UNIT_LON_16000;
             }
@@ -177,7 +193,7 @@ pub fn to_meshpoint(
                 if h[idx] / 3 == 1 {
                     lat[idx] += UNIT_LAT_4000;
                 }
-                if h[idx] % 2 == 0 {
+                if h[idx].is_multiple_of(2) {
                     lon[idx] += UNIT_LON_4000;
                 }
             }
@@ -196,7 +212,7 @@ pub fn to_meshpoint(
                 if g[idx] / 3 == 1 {
                     lat[idx] += UNIT_LAT_5000;
                 }
-                if g[idx] % 2 == 0 {
+                if g[idx].is_multiple_of(2) {
                     lon[idx] += UNIT_LON_5000;
                 }
             }
@@ -210,7 +226,7 @@ pub fn to_meshpoint(
                 if g[idx] / 3 == 1 {
                     lat[idx] += UNIT_LAT_5000;
                 }
-                if g[idx] % 2 == 0 {
+                if g[idx].is_multiple_of(2) {
                     lon[idx] += UNIT_LON_5000;
                 }
 
@@ -218,7 +234,7 @@ pub fn to_meshpoint(
                 if h[idx] / 3 == 1 {
                     lat[idx] += UNIT_LAT_2500;
                 }
-                if h[idx] % 2 == 0 {
+                if h[idx].is_multiple_of(2) {
                     lon[idx] += UNIT_LON_2500;
                 }
             }
@@ -257,7 +273,7 @@ pub fn to_meshpoint(
                 if k[idx] / 3 == 1 {
                     lat[idx] += UNIT_LAT_LV6;
                 }
-                if k[idx] % 2 == 0 {
+                if k[idx].is_multiple_of(2) {
                     lon[idx] += UNIT_LON_LV6;
                 }
             }
@@ -278,6 +294,21 @@ pub fn to_meshpoint(
     Ok(vec![lat, lon])
 }
 
+/// Calculates the centroids of mesh codes as separate latitude and longitude arrays.
+/// 地域メッシュコードの中心点の緯度経度を、緯度・経度それぞれの配列として算出する。
+///
+/// `to_meshpoint` を lat/lon_multiplier: 0.5 で呼び出したものと同じで、散布図などの描画に使えます。
+///
+/// Returns:
+/// * (緯度の配列, 経度の配列)
+pub fn to_centroid_arrays(codes: &[MeshCode]) -> Result<(Vec<f64>, Vec<f64>)> {
+    let values: Vec<u64> = codes.iter().map(|code| code.value).collect();
+    let mut points = to_meshpoint(&values, &[0.5], &[0.5])?;
+    let lon = points.pop().unwrap_or_default();
+    let lat = points.pop().unwrap_or_default();
+    Ok((lat, lon))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -332,9 +363,65 @@ mod tests {
         .unwrap();
 
         // Check results
-        for i in 0..num_elements {
-            assert_relative_eq!(result[0][i], expected_lat, epsilon = 1e-7);
-            assert_relative_eq!(result[1][i], expected_lon, epsilon = 1e-7);
+        assert_eq!(result[0].len(), num_elements);
+        for (lat, lon) in result[0].iter().zip(result[1].iter()) {
+            assert_relative_eq!(*lat, expected_lat, epsilon = 1e-7);
+            assert_relative_eq!(*lon, expected_lon, epsilon = 1e-7);
+        }
+    }
+
+    #[test]
+    fn test_to_meshpoint_empty_multipliers() {
+        assert_eq!(
+            to_meshpoint(&[5339], &[], &[0.0]),
+            Err(JismeshError::EmptyInput)
+        );
+        assert_eq!(
+            to_meshpoint(&[5339], &[0.0], &[]),
+            Err(JismeshError::EmptyInput)
+        );
+        // Nothing to compute, so empty multipliers are fine
+        assert_eq!(to_meshpoint(&[], &[], &[]), Ok(vec![vec![], vec![]]));
+    }
+
+    #[test]
+    fn test_to_meshpoint_longitude_out_of_bounds() {
+        // cd = 80 would decode to 180 degrees east
+        assert_eq!(
+            to_meshpoint(&[5380], &[0.0], &[0.0]),
+            Err(JismeshError::LongitudeOutOfBounds {
+                value: 180.0,
+                index: 0,
+            })
+        );
+        assert_eq!(
+            to_meshpoint(&[53993599], &[0.5], &[0.5]),
+            Err(JismeshError::LongitudeOutOfBounds {
+                value: 199.0,
+                index: 0,
+            })
+        );
+
+        // cd = 79 is the easternmost valid column
+        let result = to_meshpoint(&[5379], &[1.0], &[1.0]).unwrap();
+        assert_relative_eq!(result[1][0], 180.0);
+    }
+
+    #[test]
+    fn test_to_centroid_arrays() {
+        let codes: Vec<MeshCode> = [53393599, 5339, 5339467, 53393599212, 52353680]
+            .iter()
+            .map(|&v| MeshCode::try_from(v).unwrap())
+            .collect();
+        let (lat, lon) = to_centroid_arrays(&codes).unwrap();
+        assert_eq!(lat.len(), codes.len());
+        assert_eq!(lon.len(), codes.len());
+        for (i, code) in codes.iter().enumerate() {
+            let (expected_lat, expected_lon) = code.point(0.5, 0.5).unwrap();
+            assert_eq!((lat[i], lon[i]), (expected_lat, expected_lon));
         }
+
+        let (lat, lon) = to_centroid_arrays(&[]).unwrap();
+        assert!(lat.is_empty() && lon.is_empty());
     }
 }
@@ -0,0 +1,72 @@
+use super::*;
+use alloc::collections::BTreeSet;
+
+/// Checks that all mesh codes share the same level and returns it.
+/// Returns `None` if there are no mesh codes.
+pub(crate) fn common_level<'a>(
+    codes: impl IntoIterator<Item = &'a MeshCode>,
+) -> Result<Option<MeshLevel>> {
+    let mut level = None;
+    for code in codes {
+        match level {
+            None => level = Some(code.level),
+            Some(l) if l != code.level => {
+                return Err(JismeshError::MismatchedMeshLevels(l, code.level));
+            }
+            Some(_) => {}
+        }
+    }
+    Ok(level)
+}
+
+/// Returns the mesh codes that are present in exactly one of `a` and `b`.
+///
+/// Useful for change detection between two coverage snapshots.
+/// The result is sorted and contains no duplicates.
+///
+/// # Errors
+/// * Returns an error if the mesh codes are not all at the same level
+pub fn symmetric_difference(a: &[MeshCode], b: &[MeshCode]) -> Result<Vec<MeshCode>> {
+    common_level(a.iter().chain(b.iter()))?;
+
+    let a: BTreeSet<MeshCode> = a.iter().copied().collect();
+    let b: BTreeSet<MeshCode> = b.iter().copied().collect();
+    Ok(a.symmetric_difference(&b).copied().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codes(values: &[u64]) -> Vec<MeshCode> {
+        values
+            .iter()
+            .map(|&v| MeshCode::try_from(v).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_symmetric_difference() {
+        let a = codes(&[53393599, 53393598, 53393589, 53393588]);
+        let b = codes(&[53393599, 53393598, 53394509, 53394508]);
+
+        let result = symmetric_difference(&a, &b).unwrap();
+        assert_eq!(result, codes(&[53393588, 53393589, 53394508, 53394509]));
+
+        // Identical snapshots have no difference
+        assert!(symmetric_difference(&a, &a).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_symmetric_difference_mismatched_levels() {
+        let a = codes(&[53393599]);
+        let b = codes(&[533935]);
+        assert_eq!(
+            symmetric_difference(&a, &b),
+            Err(JismeshError::MismatchedMeshLevels(
+                MeshLevel::Lv3,
+                MeshLevel::Lv2
+            ))
+        );
+    }
+}
@@ -1,16 +1,88 @@
+#[cfg(not(feature = "std"))]
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+// Unused when std is linked through another crate (e.g. thiserror/std enabled by a
+// dev-dependency in tests), as the inherent float methods of std take precedence
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+use float::FloatExt;
+
 mod error;
 mod levels;
 pub use error::JismeshError;
 use error::Result;
-pub use levels::MeshLevel;
+pub use levels::{MeshLevel, SizeUnit};
 mod meshcode;
-pub use meshcode::{MeshCode, to_meshcode};
+#[cfg(feature = "rayon")]
+pub use meshcode::to_meshcode_par;
+pub use meshcode::{
+    BoundaryRule, Corner, MeshCode, is_in_valid_range, to_meshcode, to_meshcode_pairs,
+    to_meshcode_with_boundary,
+};
 mod meshlevel;
-pub use meshlevel::to_meshlevel;
+pub use meshlevel::{level_of, to_meshlevel, to_meshlevel_lenient};
 mod meshpoint;
-pub use meshpoint::to_meshpoint;
+pub use meshpoint::{to_centroid_arrays, to_meshpoint};
 mod envelope;
-pub use envelope::{to_envelope, to_intersects};
+pub use envelope::{
+    align_bbox, bounding_envelope, envelope_sample, expand_envelope, iter_in_bbox, to_envelope,
+    to_intersects, to_intersects_with_margin,
+};
+mod export;
+#[cfg(feature = "geojson")]
+pub use export::to_geojson;
+#[cfg(not(feature = "std"))]
+mod float;
+mod geodesy;
+mod grid;
+pub use grid::{Direction, Quadrant};
+mod meshset;
+mod random;
+pub use meshset::symmetric_difference;
+mod coverage;
+pub use coverage::{pack_coverage, unpack_coverage};
+#[cfg(feature = "std")]
+mod anonymity;
+#[cfg(feature = "std")]
+pub use anonymity::enforce_k_anonymity;
+mod raster;
+pub use raster::{render_grid, to_raster_indices};
+mod mesh100m;
+pub use mesh100m::Mesh100m;
+#[cfg(feature = "std")]
+mod shapes;
+#[cfg(feature = "std")]
+pub use shapes::{cover_circle, cover_line, cover_polygon, coverage_error};
+#[cfg(feature = "std")]
+mod summary;
+#[cfg(feature = "std")]
+pub use summary::{MeshSummary, level_histogram, summarize};
+#[cfg(feature = "datum")]
+mod datum;
+#[cfg(feature = "datum")]
+pub use datum::to_meshcode_tokyo_datum;
+#[cfg(feature = "geo")]
+mod geo;
+#[cfg(feature = "geopackage")]
+mod geopackage;
+#[cfg(feature = "projection")]
+mod projection;
+#[cfg(feature = "geopackage")]
+pub use geopackage::write_geopackage;
+#[cfg(feature = "rstar")]
+mod rtree;
+#[cfg(feature = "rstar")]
+pub use rtree::RTreeMeshCode;
+#[cfg(feature = "serde")]
+mod serialize;
+#[cfg(feature = "polars")]
+mod series;
+#[cfg(feature = "polars")]
+pub use series::{meshcode_series, meshpoint_series};
 
 const UNIT_LAT_LV1: f64 = 2.0 / 3.0;
 const UNIT_LON_LV1: f64 = 1.0;
@@ -68,11 +140,17 @@ pub(crate) fn unit_lon(level: MeshLevel) -> f64 {
     unit_lat_lon(level).1
 }
 
+/// Number of decimal digits of a value, computed with integers so that values at or near a
+/// power of ten (e.g. 10000 or 999999999999999999) are never off by one. 0 has 1 digit.
+pub(crate) fn num_digits(value: u64) -> u32 {
+    value.checked_ilog10().unwrap_or(0) + 1
+}
+
 pub(crate) fn slice(codes: &[u64], start: u32, stop: u32) -> Vec<u8> {
     codes
         .iter()
         .map(|&t| {
-            let num_digits = (t as f64).log10().floor() as u32 + 1;
+            let num_digits = num_digits(t);
             if num_digits < stop {
                 0
             } else {
@@ -88,6 +166,30 @@ pub(crate) fn slice(codes: &[u64], start: u32, stop: u32) -> Vec<u8> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_num_digits() {
+        assert_eq!(num_digits(0), 1);
+        assert_eq!(num_digits(9), 1);
+        for exp in 1..=19 {
+            let power = 10_u64.pow(exp);
+            assert_eq!(num_digits(power), exp + 1, "Failed for {}", power);
+            assert_eq!(num_digits(power - 1), exp, "Failed for {}", power - 1);
+        }
+        // f64 rounds this up to 1e18, which made log10 report 19 digits
+        assert_eq!(num_digits(999_999_999_999_999_999), 18);
+        assert_eq!(num_digits(u64::MAX), 20);
+    }
+
+    #[test]
+    fn test_slice_power_of_ten() {
+        assert_eq!(slice(&[10000], 0, 1), vec![1]);
+        assert_eq!(slice(&[10000], 4, 5), vec![0]);
+        assert_eq!(slice(&[100000000], 0, 1), vec![1]);
+        assert_eq!(slice(&[100000000], 8, 9), vec![0]);
+        assert_eq!(slice(&[99999999], 7, 8), vec![9]);
+        assert_eq!(slice(&[99999999], 8, 9), vec![0]);
+    }
+
     #[test]
     fn test_slice() {
         // Test single digit extraction
@@ -0,0 +1,193 @@
+use super::*;
+
+/// GRS80 semi-major axis in meters
+const GRS80_A: f64 = 6_378_137.0;
+/// GRS80 flattening
+const GRS80_F: f64 = 1.0 / 298.257_222_101;
+/// Scale factor on the central meridian of the plane rectangular coordinate system
+const M0: f64 = 0.9999;
+
+/// Origins (latitude, longitude) of the Japan Plane Rectangular CS zones I to XIX, in degrees.
+const PLANE_RECTANGULAR_ORIGINS: [(f64, f64); 19] = [
+    (33.0, 129.0 + 30.0 / 60.0),
+    (33.0, 131.0),
+    (36.0, 132.0 + 10.0 / 60.0),
+    (33.0, 133.0 + 30.0 / 60.0),
+    (36.0, 134.0 + 20.0 / 60.0),
+    (36.0, 136.0),
+    (36.0, 137.0 + 10.0 / 60.0),
+    (36.0, 138.0 + 30.0 / 60.0),
+    (36.0, 139.0 + 50.0 / 60.0),
+    (40.0, 140.0 + 50.0 / 60.0),
+    (44.0, 140.0 + 15.0 / 60.0),
+    (44.0, 142.0 + 15.0 / 60.0),
+    (44.0, 144.0 + 15.0 / 60.0),
+    (26.0, 142.0),
+    (26.0, 127.0 + 30.0 / 60.0),
+    (26.0, 124.0),
+    (26.0, 131.0),
+    (20.0, 136.0),
+    (26.0, 154.0),
+];
+
+/// Returns the origin of the plane rectangular zone for a supported EPSG code.
+///
+/// Supported: JGD2011 (EPSG:6669 - 6687) and JGD2000 (EPSG:2443 - 2461) zones I to XIX.
+/// Both datums use the GRS80 ellipsoid, so the same parameters apply.
+fn plane_rectangular_origin(epsg: u32) -> Result<(f64, f64)> {
+    let zone = match epsg {
+        6669..=6687 => epsg - 6669,
+        2443..=2461 => epsg - 2443,
+        _ => return Err(JismeshError::UnsupportedEpsgCode(epsg)),
+    };
+    Ok(PLANE_RECTANGULAR_ORIGINS[zone as usize])
+}
+
+/// Projects a latitude / longitude to the plane rectangular coordinate system of `epsg`.
+///
+/// Uses the Gauss-Krüger projection with the 5th order Krüger series, as published by
+/// the Geospatial Information Authority of Japan. Accuracy is well below a millimeter
+/// within a zone.
+///
+/// Returns (X, Y) in meters, where X points north and Y points east (the survey convention
+/// used by the Japanese plane rectangular coordinate system).
+pub(crate) fn to_plane_rectangular(lat: f64, lon: f64, epsg: u32) -> Result<(f64, f64)> {
+    let (lat0, lon0) = plane_rectangular_origin(epsg)?;
+
+    let n = GRS80_F / (2.0 - GRS80_F);
+    let n2 = n * n;
+    let n3 = n2 * n;
+    let n4 = n3 * n;
+    let n5 = n4 * n;
+
+    let alpha = [
+        n / 2.0 - 2.0 * n2 / 3.0 + 5.0 * n3 / 16.0 + 41.0 * n4 / 180.0 - 127.0 * n5 / 288.0,
+        13.0 * n2 / 48.0 - 3.0 * n3 / 5.0 + 557.0 * n4 / 1440.0 + 281.0 * n5 / 630.0,
+        61.0 * n3 / 240.0 - 103.0 * n4 / 140.0 + 15061.0 * n5 / 26880.0,
+        49561.0 * n4 / 161280.0 - 179.0 * n5 / 168.0,
+        34729.0 * n5 / 80640.0,
+    ];
+    let arc = [
+        1.0 + n2 / 4.0 + n4 / 64.0,
+        -3.0 / 2.0 * (n - n3 / 8.0 - n5 / 64.0),
+        15.0 / 16.0 * (n2 - n4 / 4.0),
+        -35.0 / 48.0 * (n3 - 5.0 * n5 / 16.0),
+        315.0 * n4 / 512.0,
+        -693.0 * n5 / 1280.0,
+    ];
+    let a_bar = M0 * GRS80_A / (1.0 + n) * arc[0];
+
+    // Meridian arc length from the equator to the origin latitude
+    let phi0 = lat0.to_radians();
+    let s_phi0 = M0 * GRS80_A / (1.0 + n)
+        * (arc[0] * phi0
+            + arc[1..]
+                .iter()
+                .enumerate()
+                .map(|(j, a)| a * (2.0 * (j + 1) as f64 * phi0).sin())
+                .sum::<f64>());
+
+    let phi = lat.to_radians();
+    let d_lambda = (lon - lon0).to_radians();
+    let k = 2.0 * n.sqrt() / (1.0 + n);
+    let t = (phi.sin().atanh() - k * (k * phi.sin()).atanh()).sinh();
+    let t_bar = (1.0 + t * t).sqrt();
+    let xi = t.atan2(d_lambda.cos());
+    let eta = (d_lambda.sin() / t_bar).atanh();
+
+    let (mut x, mut y) = (xi, eta);
+    for (j, a) in alpha.iter().enumerate() {
+        let j2 = 2.0 * (j + 1) as f64;
+        x += a * (j2 * xi).sin() * (j2 * eta).cosh();
+        y += a * (j2 * xi).cos() * (j2 * eta).sinh();
+    }
+
+    Ok((a_bar * x - s_phi0, a_bar * y))
+}
+
+impl MeshCode {
+    /// メッシュの中心点を平面直角座標系に投影した座標を取得する。
+    ///
+    /// 対応している座標系は JGD2011 (EPSG:6669〜6687) と JGD2000 (EPSG:2443〜2461) の
+    /// 平面直角座標系 第I系〜第XIX系です。
+    /// 返却値は (X, Y) (m) で、X は北向き、 Y は東向きです。
+    pub fn centroid_projected(&self, epsg: u32) -> Result<(f64, f64)> {
+        let (lat, lon) = self.point(0.5, 0.5)?;
+        to_plane_rectangular(lat, lon, epsg)
+    }
+
+    /// メッシュの四隅を平面直角座標系に投影した座標を取得する。
+    ///
+    /// 南西 → 南東 → 北東 → 北西 の順で、それぞれ (X, Y) (m) です。
+    /// 投影後は座標軸に平行な長方形にはならないため、四隅をそのまま返します。
+    /// 対応している座標系は `centroid_projected` と同じです。
+    pub fn bounds_projected(&self, epsg: u32) -> Result<[(f64, f64); 4]> {
+        let (lat_s, lon_w, lat_n, lon_e) = self.bounds()?;
+        Ok([
+            to_plane_rectangular(lat_s, lon_w, epsg)?,
+            to_plane_rectangular(lat_s, lon_e, epsg)?,
+            to_plane_rectangular(lat_n, lon_e, epsg)?,
+            to_plane_rectangular(lat_n, lon_w, epsg)?,
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_abs_diff_eq;
+
+    #[test]
+    fn test_to_plane_rectangular_origin() {
+        // The origin of zone IX projects to (0, 0)
+        let (x, y) = to_plane_rectangular(36.0, 139.0 + 50.0 / 60.0, 6677).unwrap();
+        assert_abs_diff_eq!(x, 0.0, epsilon = 1e-6);
+        assert_abs_diff_eq!(y, 0.0, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn test_centroid_projected() {
+        // Reference values computed independently with the USGS (Snyder) series
+        let meshcode = MeshCode::try_from(53393599).unwrap();
+        let (x, y) = meshcode.centroid_projected(6677).unwrap();
+        assert_abs_diff_eq!(x, -37440.167, epsilon = 1e-3);
+        assert_abs_diff_eq!(y, -8110.629, epsilon = 1e-3);
+
+        // JGD2000 zone IX uses the same parameters
+        assert_eq!(meshcode.centroid_projected(2451).unwrap(), (x, y));
+    }
+
+    #[test]
+    fn test_bounds_projected() {
+        // Reference values computed independently with the USGS (Snyder) series
+        let meshcode = MeshCode::try_from(53393599).unwrap();
+        let corners = meshcode.bounds_projected(6677).unwrap();
+        let expected = [
+            (-37901.890, -8676.938),
+            (-37902.922, -7545.163),
+            (-36978.408, -7544.379),
+            (-36977.376, -8676.036),
+        ];
+        for ((x, y), (ex, ey)) in corners.iter().zip(expected) {
+            assert_abs_diff_eq!(*x, ex, epsilon = 1e-3);
+            assert_abs_diff_eq!(*y, ey, epsilon = 1e-3);
+        }
+
+        // Meridians converge towards the pole, so the projected cell isn't axis aligned
+        assert!(corners[0].0 != corners[1].0);
+
+        assert_eq!(
+            meshcode.bounds_projected(4326),
+            Err(JismeshError::UnsupportedEpsgCode(4326))
+        );
+    }
+
+    #[test]
+    fn test_centroid_projected_unsupported_epsg() {
+        let meshcode = MeshCode::try_from(53393599).unwrap();
+        assert_eq!(
+            meshcode.centroid_projected(4326),
+            Err(JismeshError::UnsupportedEpsgCode(4326))
+        );
+    }
+}
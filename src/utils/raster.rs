@@ -0,0 +1,169 @@
+use super::meshset::common_level;
+use super::*;
+
+/// Maximum number of cells `render_grid` renders, about 1 MB of text.
+const MAX_RENDER_CELLS: usize = 1_000_000;
+
+/// Map mesh codes to pixel coordinates in the tightest raster covering their bounding box,
+/// with one pixel per mesh code.
+///
+/// # Arguments
+/// * `codes` - Mesh codes at the same level
+///
+/// # Returns
+/// * `Result<(usize, usize, Vec<(usize, usize)>)>` - The raster height and width, and the
+///   (row, col) pixel of each mesh code in input order. Row 0 is the northernmost row and
+///   col 0 the westernmost column.
+///
+/// # Errors
+/// * Returns an error if `codes` is empty or the mesh codes are not all at the same level
+#[allow(clippy::type_complexity)]
+pub fn to_raster_indices(codes: &[MeshCode]) -> Result<(usize, usize, Vec<(usize, usize)>)> {
+    common_level(codes)?.ok_or(JismeshError::EmptyInput)?;
+
+    let indexes: Vec<(u64, u64)> = codes.iter().map(|code| code.global_index()).collect();
+    let (mut row_s, mut col_w) = indexes[0];
+    let (mut row_n, mut col_e) = indexes[0];
+    for &(row, col) in &indexes {
+        row_s = row_s.min(row);
+        row_n = row_n.max(row);
+        col_w = col_w.min(col);
+        col_e = col_e.max(col);
+    }
+
+    let height = (row_n - row_s + 1) as usize;
+    let width = (col_e - col_w + 1) as usize;
+    let pixels = indexes
+        .into_iter()
+        .map(|(row, col)| ((row_n - row) as usize, (col - col_w) as usize))
+        .collect();
+    Ok((height, width, pixels))
+}
+
+/// Render mesh codes as an ASCII grid for quick inspection in a terminal.
+///
+/// Each mesh code of the bounding box is one character: `#` if it is present in `codes`
+/// and `.` if it is absent. North is at the top. Rows are separated by `\n`. The bounding
+/// box may have at most 1,000,000 mesh codes.
+///
+/// # Arguments
+/// * `codes` - Mesh codes at the same level
+///
+/// # Returns
+/// * `Result<String>` - The rendered grid
+///
+/// # Errors
+/// * Returns an error if `codes` is empty or the mesh codes are not all at the same level
+/// * Returns an error if the bounding box has more than 1,000,000 mesh codes
+pub fn render_grid(codes: &[MeshCode]) -> Result<String> {
+    let (height, width, mut pixels) = to_raster_indices(codes)?;
+    if height.saturating_mul(width) > MAX_RENDER_CELLS {
+        return Err(JismeshError::GridTooLarge {
+            height,
+            width,
+            max: MAX_RENDER_CELLS,
+        });
+    }
+
+    pixels.sort_unstable();
+    pixels.dedup();
+    let mut pixels = pixels.into_iter().peekable();
+    let mut grid = String::with_capacity(height * (width + 1));
+    for row in 0..height {
+        if row > 0 {
+            grid.push('\n');
+        }
+        for col in 0..width {
+            if pixels.next_if_eq(&(row, col)).is_some() {
+                grid.push('#');
+            } else {
+                grid.push('.');
+            }
+        }
+    }
+    Ok(grid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_raster_indices() {
+        // A 2x3 block of Lv3 mesh codes crossing a Lv2 boundary, north row first
+        let codes: Vec<MeshCode> = [58405448, 58405449, 58405540, 58405438, 58405439, 58405530]
+            .iter()
+            .map(|&v| MeshCode::try_from(v).unwrap())
+            .collect();
+        let (height, width, pixels) = to_raster_indices(&codes).unwrap();
+        assert_eq!((height, width), (2, 3));
+        assert_eq!(pixels, vec![(0, 0), (0, 1), (0, 2), (1, 0), (1, 1), (1, 2)]);
+    }
+
+    #[test]
+    fn test_to_raster_indices_sparse() {
+        let a = MeshCode::try_from(53393599).unwrap();
+        let b = a.step(-2, 3).unwrap();
+        let (height, width, pixels) = to_raster_indices(&[a, b]).unwrap();
+        assert_eq!((height, width), (3, 4));
+        assert_eq!(pixels, vec![(0, 0), (2, 3)]);
+    }
+
+    #[test]
+    fn test_render_grid() {
+        let a = MeshCode::try_from(53393599).unwrap();
+        let block = [
+            a,
+            a.step(0, 1).unwrap(),
+            a.step(1, 0).unwrap(),
+            a.step(1, 1).unwrap(),
+        ];
+        assert_eq!(render_grid(&block).unwrap(), "##\n##");
+
+        // An L shape, with the corner in the south-west
+        let shape = [a, a.step(0, 2).unwrap(), a.step(1, 0).unwrap()];
+        assert_eq!(render_grid(&shape).unwrap(), "#..\n#.#");
+
+        assert_eq!(render_grid(&[]), Err(JismeshError::EmptyInput));
+
+        // Duplicates are rendered once
+        assert_eq!(render_grid(&[a, a]).unwrap(), "#");
+    }
+
+    #[test]
+    fn test_render_grid_too_large() {
+        // Two distant Lv6 mesh codes span a huge bounding box, which is rejected up front
+        let a = MeshCode::try_from_latlng(24.0, 123.0, MeshLevel::Lv6).unwrap();
+        let b = MeshCode::try_from_latlng(45.0, 145.0, MeshLevel::Lv6).unwrap();
+        let (height, width, _) = to_raster_indices(&[a, b]).unwrap();
+        assert_eq!(
+            render_grid(&[a, b]),
+            Err(JismeshError::GridTooLarge {
+                height,
+                width,
+                max: 1_000_000,
+            })
+        );
+
+        // A 1000 x 1000 grid is just within the limit
+        let b = a.step(999, 999).unwrap();
+        let grid = render_grid(&[a, b]).unwrap();
+        assert_eq!(grid.len(), 1000 * 1001 - 1);
+    }
+
+    #[test]
+    fn test_to_raster_indices_errors() {
+        assert_eq!(to_raster_indices(&[]), Err(JismeshError::EmptyInput));
+        let codes = [
+            MeshCode::try_from(53393599).unwrap(),
+            MeshCode::try_from(533935).unwrap(),
+        ];
+        assert_eq!(
+            to_raster_indices(&codes),
+            Err(JismeshError::MismatchedMeshLevels(
+                MeshLevel::Lv3,
+                MeshLevel::Lv2
+            ))
+        );
+    }
+}
@@ -0,0 +1,126 @@
+use super::*;
+use rstar::{AABB, Envelope, PointDistance, RTreeObject};
+
+/// `rstar::RTree` に格納できるメッシュコード
+///
+/// 格納時に範囲を計算するため、範囲を求められないメッシュコード（例: 経度の桁が範囲外の
+/// 538000 ）は `RTreeMeshCode::try_from` でエラーになります。
+/// 座標は `point` や `bounds` と同じく [緯度, 経度] の順です。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RTreeMeshCode {
+    code: MeshCode,
+    envelope: AABB<[f64; 2]>,
+}
+
+impl RTreeMeshCode {
+    /// 格納されているメッシュコードを取得する。
+    pub fn code(&self) -> MeshCode {
+        self.code
+    }
+}
+
+impl TryFrom<MeshCode> for RTreeMeshCode {
+    type Error = JismeshError;
+
+    fn try_from(code: MeshCode) -> Result<Self> {
+        let (lat_s, lon_w, lat_n, lon_e) = code.bounds()?;
+        Ok(RTreeMeshCode {
+            code,
+            envelope: AABB::from_corners([lat_s, lon_w], [lat_n, lon_e]),
+        })
+    }
+}
+
+impl RTreeObject for RTreeMeshCode {
+    type Envelope = AABB<[f64; 2]>;
+
+    fn envelope(&self) -> Self::Envelope {
+        self.envelope
+    }
+}
+
+/// メッシュと点の距離の二乗（度単位）。点がメッシュ内（境界を含む）にある場合は 0 です。
+impl PointDistance for RTreeMeshCode {
+    fn distance_2(&self, point: &[f64; 2]) -> f64 {
+        self.envelope.distance_2(point)
+    }
+
+    fn contains_point(&self, point: &[f64; 2]) -> bool {
+        self.envelope.contains_point(point)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstar::RTree;
+
+    fn tree(sw: &MeshCode, ne: &MeshCode) -> RTree<RTreeMeshCode> {
+        let codes = to_envelope(sw, ne).unwrap();
+        RTree::bulk_load(
+            codes
+                .into_iter()
+                .map(RTreeMeshCode::try_from)
+                .collect::<Result<Vec<_>>>()
+                .unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_rtree_meshcode_try_from() {
+        let code = MeshCode::try_from(53393599).unwrap();
+        let object = RTreeMeshCode::try_from(code).unwrap();
+        assert_eq!(object.code(), code);
+        let (lat_s, lon_w, lat_n, lon_e) = code.bounds().unwrap();
+        assert_eq!(object.envelope().lower(), [lat_s, lon_w]);
+        assert_eq!(object.envelope().upper(), [lat_n, lon_e]);
+
+        // The longitude digits of 538000 and 5380 are out of range, so they have no bounds
+        for value in [538000, 5380] {
+            let code = MeshCode::try_from(value).unwrap();
+            assert!(RTreeMeshCode::try_from(code).is_err());
+        }
+    }
+
+    #[test]
+    fn test_rtree_locate_in_envelope() {
+        let sw = MeshCode::try_from(53393500).unwrap();
+        let ne = sw.step(19, 19).unwrap();
+        let tree = tree(&sw, &ne);
+        assert_eq!(tree.size(), 400);
+
+        // A window slightly larger than a 2 x 3 block returns exactly that block
+        let block_sw = MeshCode::try_from(53393599).unwrap();
+        let block_ne = block_sw.step(1, 2).unwrap();
+        let (lat_s, lon_w, _, _) = block_sw.bounds().unwrap();
+        let (_, _, lat_n, lon_e) = block_ne.bounds().unwrap();
+        let d = 1e-6;
+        let window = AABB::from_corners([lat_s - d, lon_w - d], [lat_n + d, lon_e + d]);
+        let mut found: Vec<MeshCode> = tree
+            .locate_in_envelope(&window)
+            .map(|object| object.code())
+            .collect();
+        found.sort_by_key(|code| code.global_index());
+        assert_eq!(found, to_envelope(&block_sw, &block_ne).unwrap());
+    }
+
+    #[test]
+    fn test_rtree_nearest_neighbor() {
+        let sw = MeshCode::try_from(53393500).unwrap();
+        let ne = sw.step(9, 9).unwrap();
+        let tree = tree(&sw, &ne);
+
+        // A point inside a mesh finds that mesh
+        let (lat, lon) = (35.658581, 139.745433);
+        assert_eq!(tree.nearest_neighbor(&[lat, lon]).unwrap().code(), 53393599);
+        assert_eq!(tree.locate_at_point(&[lat, lon]).unwrap().code(), 53393599);
+
+        // A point outside the grid finds the closest mesh on its edge
+        let (_, _, _, lon_e) = ne.bounds().unwrap();
+        let (lat, _) = ne.point(0.5, 0.5).unwrap();
+        assert_eq!(
+            tree.nearest_neighbor(&[lat, lon_e + 0.001]).unwrap().code(),
+            ne
+        );
+    }
+}
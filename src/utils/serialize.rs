@@ -0,0 +1,133 @@
+use super::*;
+use core::fmt;
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// メッシュコードの数字の文字列（例: `"53393599"`）としてシリアライズする。
+impl Serialize for MeshCode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// メッシュコードの数字の文字列、または数値からデシリアライズする。
+/// 数値を受け付けるのは JSON などの自己記述的な形式のみで、バイナリ形式では
+/// `Serialize` と同じく文字列として読み込みます。
+/// 次数はメッシュコードから判定し直すため、無効なメッシュコードはエラーになります。
+impl<'de> Deserialize<'de> for MeshCode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(MeshCodeVisitor)
+        } else {
+            deserializer.deserialize_str(MeshCodeVisitor)
+        }
+    }
+}
+
+struct MeshCodeVisitor;
+
+impl Visitor<'_> for MeshCodeVisitor {
+    type Value = MeshCode;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a mesh code as a string or an unsigned integer")
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> std::result::Result<MeshCode, E> {
+        MeshCode::try_from(value).map_err(E::custom)
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> std::result::Result<MeshCode, E> {
+        let value = u64::try_from(value)
+            .map_err(|_| E::invalid_value(de::Unexpected::Signed(value), &self))?;
+        self.visit_u64(value)
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> std::result::Result<MeshCode, E> {
+        value.parse().map_err(E::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn test_meshcode_json_round_trip() {
+        for value in [
+            5339,
+            53392,
+            5339467,
+            533935,
+            533935446,
+            53393599,
+            53393599212,
+        ] {
+            let meshcode = MeshCode::try_from(value).unwrap();
+            let json = serde_json::to_string(&meshcode).unwrap();
+            assert_eq!(json, format!("\"{}\"", value));
+            let parsed: MeshCode = serde_json::from_str(&json).unwrap();
+            assert_eq!(parsed, meshcode);
+            assert_eq!(parsed.level, meshcode.level);
+        }
+
+        // Bare numbers are accepted as well
+        let parsed: MeshCode = serde_json::from_str("53393599").unwrap();
+        assert_eq!(parsed, 53393599);
+        assert_eq!(parsed.level, MeshLevel::Lv3);
+    }
+
+    #[test]
+    fn test_meshcode_postcard_round_trip() {
+        // postcard is not self-describing, so it can't deserialize_any
+        for value in [5339, 53392, 533935, 53393599, 53393599212] {
+            let meshcode = MeshCode::try_from(value).unwrap();
+            let bytes = postcard::to_allocvec(&meshcode).unwrap();
+            let parsed: MeshCode = postcard::from_bytes(&bytes).unwrap();
+            assert_eq!(parsed, meshcode);
+            assert_eq!(parsed.level, meshcode.level);
+        }
+
+        let bytes = postcard::to_allocvec("12345678901234").unwrap();
+        assert!(postcard::from_bytes::<MeshCode>(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_meshcode_json_invalid() {
+        for json in ["\"12345678901234\"", "\"abc\"", "-1", "1.5", "null", "\"\""] {
+            assert!(
+                serde_json::from_str::<MeshCode>(json).is_err(),
+                "Failed for {}",
+                json
+            );
+        }
+    }
+
+    #[test]
+    fn test_meshlevel_json_round_trip() {
+        for level in MeshLevel::iter() {
+            let json = serde_json::to_string(&level).unwrap();
+            assert_eq!(json, format!("\"{}\"", level));
+            assert_eq!(serde_json::from_str::<MeshLevel>(&json).unwrap(), level);
+        }
+        assert_eq!(serde_json::to_string(&MeshLevel::X2_5).unwrap(), "\"X2_5\"");
+        assert!(serde_json::from_str::<MeshLevel>("\"Lv7\"").is_err());
+    }
+
+    #[test]
+    fn test_struct_with_meshcode() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Record {
+            code: MeshCode,
+            level: MeshLevel,
+        }
+        let record = Record {
+            code: MeshCode::try_from(53393599).unwrap(),
+            level: MeshLevel::Lv3,
+        };
+        let json = serde_json::to_string(&record).unwrap();
+        assert_eq!(json, r#"{"code":"53393599","level":"Lv3"}"#);
+        assert_eq!(serde_json::from_str::<Record>(&json).unwrap(), record);
+    }
+}
@@ -0,0 +1,141 @@
+use super::*;
+use polars::prelude::*;
+
+/// Converts a `Series` of latitudes and a `Series` of longitudes into a `Series` of
+/// mesh codes (`u64`) at the given level, named `meshcode`.
+///
+/// Numeric input columns are cast to `f64`. Rows where either coordinate is null
+/// produce a null mesh code.
+///
+/// # Errors
+/// * Returns an error if the series have different lengths or can't be cast to `f64`
+/// * Returns a `ComputeError` if a coordinate is out of bounds
+pub fn meshcode_series(lat: &Series, lon: &Series, level: MeshLevel) -> PolarsResult<Series> {
+    polars_ensure!(
+        lat.len() == lon.len(),
+        ShapeMismatch: "lat and lon must have the same length ({} != {})", lat.len(), lon.len()
+    );
+    let lat = lat.cast(&DataType::Float64)?;
+    let lon = lon.cast(&DataType::Float64)?;
+
+    let codes = lat
+        .f64()?
+        .iter()
+        .zip(lon.f64()?.iter())
+        .map(|(lat, lon)| match (lat, lon) {
+            (Some(lat), Some(lon)) => MeshCode::try_from_latlng(lat, lon, level)
+                .map(|code| Some(code.value))
+                .map_err(|e| polars_err!(ComputeError: "{}", e)),
+            _ => Ok(None),
+        })
+        .collect::<PolarsResult<UInt64Chunked>>()?;
+
+    Ok(codes.with_name("meshcode".into()).into_series())
+}
+
+/// Converts a `Series` of mesh codes into `Series` of latitudes and longitudes, named
+/// `lat` and `lon`. The multipliers work the same way as `to_meshpoint`.
+///
+/// Numeric input columns are cast to `u64`. Null mesh codes produce null coordinates.
+///
+/// # Errors
+/// * Returns an error if the series can't be cast to `u64`
+/// * Returns a `ComputeError` if a mesh code is invalid
+pub fn meshpoint_series(
+    codes: &Series,
+    lat_multiplier: f64,
+    lon_multiplier: f64,
+) -> PolarsResult<(Series, Series)> {
+    let codes = codes.cast(&DataType::UInt64)?;
+
+    let points = codes
+        .u64()?
+        .iter()
+        .map(|code| match code {
+            Some(code) => MeshCode::try_from(code)
+                .and_then(|code| code.point(lat_multiplier, lon_multiplier))
+                .map(Some)
+                .map_err(|e| polars_err!(ComputeError: "{}", e)),
+            None => Ok(None),
+        })
+        .collect::<PolarsResult<Vec<Option<(f64, f64)>>>>()?;
+
+    let lat: Float64Chunked = points.iter().map(|p| p.map(|(lat, _)| lat)).collect();
+    let lon: Float64Chunked = points.iter().map(|p| p.map(|(_, lon)| lon)).collect();
+    Ok((
+        lat.with_name("lat".into()).into_series(),
+        lon.with_name("lon".into()).into_series(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    fn sample() -> DataFrame {
+        DataFrame::new(
+            3,
+            vec![
+                Series::new("lat".into(), &[Some(35.658581), Some(34.987574), None]).into(),
+                Series::new("lon".into(), &[139.745433, 135.759363, 139.0]).into(),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_meshcode_series() {
+        let df = sample();
+        let lat = df.column("lat").unwrap().as_materialized_series();
+        let lon = df.column("lon").unwrap().as_materialized_series();
+
+        let codes = meshcode_series(lat, lon, MeshLevel::Lv3).unwrap();
+        assert_eq!(codes.name().as_str(), "meshcode");
+        let codes: Vec<Option<u64>> = codes.u64().unwrap().iter().collect();
+        assert_eq!(codes, vec![Some(53393599), Some(52353680), None]);
+    }
+
+    #[test]
+    fn test_meshcode_series_errors() {
+        let lat = Series::new("lat".into(), &[35.658581, 80.0]);
+        let lon = Series::new("lon".into(), &[139.745433, 139.745433]);
+        assert!(meshcode_series(&lat, &lon, MeshLevel::Lv3).is_err());
+
+        let lon = Series::new("lon".into(), &[139.745433]);
+        assert!(meshcode_series(&lat, &lon, MeshLevel::Lv3).is_err());
+    }
+
+    #[test]
+    fn test_meshpoint_series_round_trip() {
+        let df = sample();
+        let lat = df.column("lat").unwrap().as_materialized_series();
+        let lon = df.column("lon").unwrap().as_materialized_series();
+        let codes = meshcode_series(lat, lon, MeshLevel::Lv3).unwrap();
+
+        let (lat, lon) = meshpoint_series(&codes, 0.5, 0.5).unwrap();
+        let lat: Vec<Option<f64>> = lat.f64().unwrap().iter().collect();
+        let lon: Vec<Option<f64>> = lon.f64().unwrap().iter().collect();
+        for (actual, expected) in lat.iter().zip([Some(35.6625), Some(34.9875), None]) {
+            assert_eq!(actual.is_some(), expected.is_some());
+            if let (Some(a), Some(e)) = (actual, expected) {
+                assert_relative_eq!(*a, e, epsilon = 1e-9);
+            }
+        }
+        for (actual, expected) in lon.iter().zip([Some(139.74375), Some(135.75625), None]) {
+            assert_eq!(actual.is_some(), expected.is_some());
+            if let (Some(a), Some(e)) = (actual, expected) {
+                assert_relative_eq!(*a, e, epsilon = 1e-9);
+            }
+        }
+
+        // Converting the centroids back gives the same codes
+        let lat = Series::new("lat".into(), &lat);
+        let lon = Series::new("lon".into(), &lon);
+        let again = meshcode_series(&lat, &lon, MeshLevel::Lv3).unwrap();
+        assert_eq!(
+            again.u64().unwrap().iter().collect::<Vec<_>>(),
+            codes.u64().unwrap().iter().collect::<Vec<_>>()
+        );
+    }
+}
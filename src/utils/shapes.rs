@@ -0,0 +1,512 @@
+use super::grid::GridIter;
+use super::meshcode::validate_latlng;
+use super::*;
+use std::collections::HashSet;
+
+/// Generate the mesh codes whose centroid lies inside a polygon.
+///
+/// Cells south of latitude 6.67 (Lv1 rows 0 to 9) have no valid mesh code and are skipped.
+///
+/// # Arguments
+/// * `polygon` - Vertices of the polygon as (lat, lon). The ring is closed automatically,
+///   so the first point doesn't need to be repeated at the end.
+/// * `level` - Mesh level of the result
+///
+/// # Returns
+/// * `Result<Vec<MeshCode>>` - Mesh codes in row-major order from south-west
+///
+/// # Errors
+/// * Returns an error if the polygon has fewer than 3 distinct points
+/// * Returns an error if a vertex is out of bounds
+pub fn cover_polygon(polygon: &[(f64, f64)], level: MeshLevel) -> Result<Vec<MeshCode>> {
+    let mut points = polygon.to_vec();
+    if points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+    if points.len() < 3 {
+        return Err(JismeshError::InvalidPolygon(points.len()));
+    }
+    validate_points(&points)?;
+
+    let (lat_s, lon_w, lat_n, lon_e) = bbox(&points);
+    Ok(GridIter::from_bbox(level, lat_s, lon_w, lat_n, lon_e)
+        .filter(|code| match code.point(0.5, 0.5) {
+            Ok((lat, lon)) => contains_point(&points, lat, lon),
+            Err(_) => false,
+        })
+        .collect())
+}
+
+/// Generate the mesh codes crossed by a polyline.
+///
+/// Cells south of latitude 6.67 (Lv1 rows 0 to 9) have no valid mesh code and are skipped.
+///
+/// # Arguments
+/// * `line` - Points of the line as (lat, lon)
+/// * `level` - Mesh level of the result
+///
+/// # Returns
+/// * `Result<Vec<MeshCode>>` - Mesh codes in the order the line reaches them, without
+///   duplicates
+///
+/// # Errors
+/// * Returns an error if the line has fewer than 2 points
+/// * Returns an error if a point is out of bounds
+pub fn cover_line(line: &[(f64, f64)], level: MeshLevel) -> Result<Vec<MeshCode>> {
+    if line.len() < 2 {
+        return Err(JismeshError::InvalidLine(line.len()));
+    }
+    validate_points(line)?;
+
+    let mut seen = HashSet::new();
+    let mut codes = Vec::new();
+    for segment in line.windows(2) {
+        let (lat_s, lon_w, lat_n, lon_e) = bbox(segment);
+        let (lat0, lon0) = segment[0];
+        // Each crossed mesh code with its squared distance from the start point
+        let mut crossed: Vec<(MeshCode, f64)> =
+            GridIter::from_bbox(level, lat_s, lon_w, lat_n, lon_e)
+                .filter_map(|code| {
+                    let (s, w, n, e) = code.bounds().ok()?;
+                    if !segment_crosses_rect(segment[0], segment[1], (s, w, n, e)) {
+                        return None;
+                    }
+                    let (lat, lon) = ((s + n) / 2.0, (w + e) / 2.0);
+                    Some((code, (lat - lat0).powi(2) + (lon - lon0).powi(2)))
+                })
+                .collect();
+        // Order the mesh codes of this segment from its start point
+        crossed.sort_by(|(_, d_a), (_, d_b)| d_a.total_cmp(d_b));
+        codes.extend(
+            crossed
+                .into_iter()
+                .map(|(code, _)| code)
+                .filter(|code| seen.insert(*code)),
+        );
+    }
+    Ok(codes)
+}
+
+/// Generate the mesh codes that intersect a circle.
+///
+/// The circle is clipped to the valid range of the mesh system. Cells south of latitude 6.67
+/// (Lv1 rows 0 to 9) have no valid mesh code and are skipped.
+///
+/// # Arguments
+/// * `lat` - Latitude of the center
+/// * `lon` - Longitude of the center
+/// * `radius_m` - Radius in meters (great-circle distance)
+/// * `level` - Mesh level of the result
+///
+/// # Returns
+/// * `Result<Vec<MeshCode>>` - Mesh codes in row-major order from south-west
+///
+/// # Errors
+/// * Returns an error if the radius is negative
+/// * Returns an error if the radius is not a finite number
+/// * Returns an error if the center is out of bounds
+pub fn cover_circle(lat: f64, lon: f64, radius_m: f64, level: MeshLevel) -> Result<Vec<MeshCode>> {
+    if !radius_m.is_finite() {
+        return Err(JismeshError::NonFiniteRadius(radius_m));
+    }
+    if radius_m < 0.0 {
+        return Err(JismeshError::NegativeRadius(radius_m));
+    }
+    // Validates the center
+    MeshCode::try_from_latlng(lat, lon, level)?;
+
+    let d_lat = (radius_m / geodesy::EARTH_RADIUS_M).to_degrees();
+    let d_lon = d_lat / lat.to_radians().cos();
+    let lat_s = (lat - d_lat).max(0.0);
+    let lon_w = (lon - d_lon).max(100.0);
+    let lat_n = (lat + d_lat).min(100.0 * UNIT_LAT_LV1);
+    let lon_e = (lon + d_lon).min(180.0);
+    Ok(GridIter::from_bbox(level, lat_s, lon_w, lat_n, lon_e)
+        .filter(|code| match code.bounds() {
+            Ok((s, w, n, e)) => {
+                // The nearest point of the mesh to the center
+                let (near_lat, near_lon) = (lat.clamp(s, n), lon.clamp(w, e));
+                geodesy::haversine_distance(lat, lon, near_lat, near_lon) <= radius_m
+            }
+            Err(_) => false,
+        })
+        .collect())
+}
+
+/// Validates the bounds of (lat, lon) points, reporting the index of the first invalid one.
+fn validate_points(points: &[(f64, f64)]) -> Result<()> {
+    let (lat, lon): (Vec<f64>, Vec<f64>) = points.iter().copied().unzip();
+    validate_latlng(&lat, &lon)
+}
+
+/// Measure how well mesh codes approximate a polygon.
+///
+/// Areas are computed in the latitude/longitude plane, which is accurate enough for
+/// comparing levels over a polygon of city or prefecture size. The mesh codes must not
+/// overlap each other.
+///
+/// # Arguments
+/// * `codes` - Mesh codes approximating the polygon, e.g. from `cover_polygon`
+/// * `polygon` - Vertices of the polygon as (lat, lon), as in `cover_polygon`
+///
+/// # Returns
+/// * `Result<(f64, f64)>` - The fraction of the mesh area outside the polygon, and the
+///   fraction of the polygon area not covered by the mesh codes
+///
+/// # Errors
+/// * Returns an error if `codes` is empty
+/// * Returns an error if the polygon has fewer than 3 distinct points
+/// * Returns an error if the polygon has no area, e.g. all points are on a line
+pub fn coverage_error(codes: &[MeshCode], polygon: &[(f64, f64)]) -> Result<(f64, f64)> {
+    let mut points = polygon.to_vec();
+    if points.len() > 1 && points.first() == points.last() {
+        points.pop();
+    }
+    if points.len() < 3 {
+        return Err(JismeshError::InvalidPolygon(points.len()));
+    }
+    let polygon_area = area(&points);
+    if polygon_area == 0.0 {
+        return Err(JismeshError::DegeneratePolygon(points.len()));
+    }
+    if codes.is_empty() {
+        return Err(JismeshError::EmptyInput);
+    }
+
+    let mut mesh_area = 0.0;
+    let mut covered_area = 0.0;
+    for code in codes {
+        let rect = code.bounds()?;
+        let (s, w, n, e) = rect;
+        mesh_area += (n - s) * (e - w);
+        covered_area += area(&clip_to_rect(&points, rect));
+    }
+
+    Ok((
+        ((mesh_area - covered_area) / mesh_area).max(0.0),
+        ((polygon_area - covered_area) / polygon_area).max(0.0),
+    ))
+}
+
+/// Absolute area of a polygon with the shoelace formula.
+fn area(points: &[(f64, f64)]) -> f64 {
+    if points.len() < 3 {
+        return 0.0;
+    }
+    let mut sum = 0.0;
+    for i in 0..points.len() {
+        let (y0, x0) = points[i];
+        let (y1, x1) = points[(i + 1) % points.len()];
+        sum += x0 * y1 - x1 * y0;
+    }
+    sum.abs() / 2.0
+}
+
+/// Sutherland-Hodgman clipping of a polygon to a (lat_s, lon_w, lat_n, lon_e) rectangle.
+fn clip_to_rect(points: &[(f64, f64)], rect: (f64, f64, f64, f64)) -> Vec<(f64, f64)> {
+    let (s, w, n, e) = rect;
+    // Each edge of the rectangle as (inside test, edge value, whether the edge is a latitude)
+    type Inside = fn((f64, f64), f64) -> bool;
+    let edges: [(Inside, f64, bool); 4] = [
+        (|p, v| p.0 >= v, s, true),
+        (|p, v| p.0 <= v, n, true),
+        (|p, v| p.1 >= v, w, false),
+        (|p, v| p.1 <= v, e, false),
+    ];
+
+    let mut output = points.to_vec();
+    for (inside, value, is_lat) in edges {
+        let input = std::mem::take(&mut output);
+        for i in 0..input.len() {
+            let current = input[i];
+            let previous = input[(i + input.len() - 1) % input.len()];
+            let cross = |a: (f64, f64), b: (f64, f64)| {
+                if is_lat {
+                    let t = (value - a.0) / (b.0 - a.0);
+                    (value, a.1 + (b.1 - a.1) * t)
+                } else {
+                    let t = (value - a.1) / (b.1 - a.1);
+                    (a.0 + (b.0 - a.0) * t, value)
+                }
+            };
+            match (inside(current, value), inside(previous, value)) {
+                (true, true) => output.push(current),
+                (true, false) => {
+                    output.push(cross(previous, current));
+                    output.push(current);
+                }
+                (false, true) => output.push(cross(previous, current)),
+                (false, false) => {}
+            }
+        }
+        if output.is_empty() {
+            break;
+        }
+    }
+    output
+}
+
+/// Bounding box of points as (lat_s, lon_w, lat_n, lon_e).
+fn bbox(points: &[(f64, f64)]) -> (f64, f64, f64, f64) {
+    points.iter().fold(
+        (
+            f64::INFINITY,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::NEG_INFINITY,
+        ),
+        |(s, w, n, e), &(lat, lon)| (s.min(lat), w.min(lon), n.max(lat), e.max(lon)),
+    )
+}
+
+/// Even-odd rule point in polygon test.
+fn contains_point(polygon: &[(f64, f64)], lat: f64, lon: f64) -> bool {
+    let mut inside = false;
+    let mut j = polygon.len() - 1;
+    for i in 0..polygon.len() {
+        let (lat_i, lon_i) = polygon[i];
+        let (lat_j, lon_j) = polygon[j];
+        if (lat_i > lat) != (lat_j > lat)
+            && lon < (lon_j - lon_i) * (lat - lat_i) / (lat_j - lat_i) + lon_i
+        {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+/// Liang-Barsky test of whether the segment from `a` to `b` crosses the interior of a
+/// (lat_s, lon_w, lat_n, lon_e) rectangle.
+fn segment_crosses_rect(a: (f64, f64), b: (f64, f64), rect: (f64, f64, f64, f64)) -> bool {
+    let (s, w, n, e) = rect;
+    let (d_lat, d_lon) = (b.0 - a.0, b.1 - a.1);
+    let (mut t0, mut t1) = (0.0_f64, 1.0_f64);
+    for (p, q) in [
+        (-d_lat, a.0 - s),
+        (d_lat, n - a.0),
+        (-d_lon, a.1 - w),
+        (d_lon, e - a.1),
+    ] {
+        if p == 0.0 {
+            if q <= 0.0 {
+                return false;
+            }
+        } else {
+            let t = q / p;
+            if p < 0.0 {
+                t0 = t0.max(t);
+            } else {
+                t1 = t1.min(t);
+            }
+        }
+    }
+    t0 < t1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(codes: Vec<MeshCode>) -> Vec<u64> {
+        codes.into_iter().map(|c| c.into()).collect()
+    }
+
+    #[test]
+    fn test_cover_polygon() {
+        // A triangle over the south-west half of 533935. The north-west corner is moved
+        // slightly south so that no centroid lies exactly on the diagonal.
+        let lv2 = MeshCode::try_from(533935).unwrap();
+        let sw = lv2.point(0.0, 0.0).unwrap();
+        let se = lv2.point(0.0, 1.0).unwrap();
+        let nw = lv2.point(0.95, 0.0).unwrap();
+        let codes = cover_polygon(&[sw, se, nw, sw], MeshLevel::Lv3).unwrap();
+        // 9 + 8 + ... + 0 Lv3 mesh codes per column from west to east
+        assert_eq!(codes.len(), 45);
+        assert_eq!(codes[0], 53393500);
+        for code in &codes {
+            assert!(lv2.contains(code));
+        }
+    }
+
+    #[test]
+    fn test_cover_line() {
+        // An east-west line through the middle of a row of Lv3 mesh codes
+        let a = MeshCode::try_from(53393500)
+            .unwrap()
+            .point(0.5, 0.5)
+            .unwrap();
+        let b = MeshCode::try_from(53393503)
+            .unwrap()
+            .point(0.5, 0.5)
+            .unwrap();
+        let codes = cover_line(&[a, b], MeshLevel::Lv3).unwrap();
+        assert_eq!(values(codes), vec![53393500, 53393501, 53393502, 53393503]);
+
+        // Going back west doesn't repeat mesh codes
+        let codes = cover_line(&[b, a], MeshLevel::Lv3).unwrap();
+        assert_eq!(values(codes), vec![53393503, 53393502, 53393501, 53393500]);
+        let codes = cover_line(&[a, b, a], MeshLevel::Lv3).unwrap();
+        assert_eq!(codes.len(), 4);
+    }
+
+    #[test]
+    fn test_cover_circle() {
+        let (lat, lon) = MeshCode::try_from(53393599)
+            .unwrap()
+            .point(0.5, 0.5)
+            .unwrap();
+        // A tiny circle stays inside one mesh code
+        let codes = cover_circle(lat, lon, 10.0, MeshLevel::Lv3).unwrap();
+        assert_eq!(values(codes), vec![53393599]);
+        // A 1km circle reaches the 8 neighbours, but not the ones 2 cells away
+        let codes = cover_circle(lat, lon, 1000.0, MeshLevel::Lv3).unwrap();
+        assert_eq!(codes.len(), 9);
+        let codes = cover_circle(lat, lon, 0.0, MeshLevel::Lv3).unwrap();
+        assert_eq!(values(codes), vec![53393599]);
+    }
+
+    #[test]
+    fn test_coverage_error() {
+        // A circle of about 15 km radius around the center of 533935
+        let (lat, lon) = MeshCode::try_from(533935).unwrap().point(0.5, 0.5).unwrap();
+        let circle: Vec<(f64, f64)> = (0..64)
+            .map(|i| {
+                let angle = i as f64 / 64.0 * std::f64::consts::TAU;
+                (lat + 0.135 * angle.sin(), lon + 0.165 * angle.cos())
+            })
+            .collect();
+
+        let lv2 = cover_polygon(&circle, MeshLevel::Lv2).unwrap();
+        let lv3 = cover_polygon(&circle, MeshLevel::Lv3).unwrap();
+        let (outside_lv2, uncovered_lv2) = coverage_error(&lv2, &circle).unwrap();
+        let (outside_lv3, uncovered_lv3) = coverage_error(&lv3, &circle).unwrap();
+        for error in [outside_lv2, uncovered_lv2, outside_lv3, uncovered_lv3] {
+            assert!((0.0..1.0).contains(&error));
+        }
+        // A finer level reduces the error
+        assert!(outside_lv3 < outside_lv2);
+        assert!(uncovered_lv3 < uncovered_lv2);
+        assert!(outside_lv3 + uncovered_lv3 < 0.05);
+    }
+
+    #[test]
+    fn test_coverage_error_exact() {
+        // A polygon that is exactly one Lv2 mesh
+        let meshcode = MeshCode::try_from(533935).unwrap();
+        let (s, w, n, e) = meshcode.bounds().unwrap();
+        let square = [(s, w), (s, e), (n, e), (n, w)];
+        let (outside, uncovered) = coverage_error(&[meshcode], &square).unwrap();
+        assert!(outside < 1e-9);
+        assert!(uncovered < 1e-9);
+
+        // Half of the mesh is outside a polygon covering its southern half
+        let mid = (s + n) / 2.0;
+        let half = [(s, w), (s, e), (mid, e), (mid, w)];
+        let (outside, uncovered) = coverage_error(&[meshcode], &half).unwrap();
+        assert!((outside - 0.5).abs() < 1e-9);
+        assert!(uncovered < 1e-9);
+
+        assert_eq!(coverage_error(&[], &square), Err(JismeshError::EmptyInput));
+        assert_eq!(
+            coverage_error(&[meshcode], &square[..2]),
+            Err(JismeshError::InvalidPolygon(2))
+        );
+
+        // Collinear points have no area
+        let line = [(s, w), (mid, w), (n, w)];
+        assert_eq!(
+            coverage_error(&[meshcode], &line),
+            Err(JismeshError::DegeneratePolygon(3))
+        );
+    }
+
+    #[test]
+    fn test_cover_errors() {
+        assert_eq!(
+            cover_polygon(&[], MeshLevel::Lv3),
+            Err(JismeshError::InvalidPolygon(0))
+        );
+        assert_eq!(
+            cover_polygon(
+                &[(35.0, 139.0), (35.1, 139.0), (35.0, 139.0)],
+                MeshLevel::Lv3
+            ),
+            Err(JismeshError::InvalidPolygon(2))
+        );
+        assert_eq!(
+            cover_line(&[(35.0, 139.0)], MeshLevel::Lv3),
+            Err(JismeshError::InvalidLine(1))
+        );
+        assert_eq!(
+            cover_circle(35.0, 139.0, -1.0, MeshLevel::Lv3),
+            Err(JismeshError::NegativeRadius(-1.0))
+        );
+        assert_eq!(
+            cover_circle(80.0, 139.0, 1.0, MeshLevel::Lv3),
+            Err(JismeshError::LatitudeOutOfBounds {
+                value: 80.0,
+                index: 0,
+            })
+        );
+        assert!(matches!(
+            cover_circle(35.0, 139.0, f64::NAN, MeshLevel::Lv3),
+            Err(JismeshError::NonFiniteRadius(r)) if r.is_nan()
+        ));
+        assert_eq!(
+            cover_circle(35.0, 139.0, f64::INFINITY, MeshLevel::Lv3),
+            Err(JismeshError::NonFiniteRadius(f64::INFINITY))
+        );
+
+        // Vertices are validated, reporting the index of the first invalid one
+        assert_eq!(
+            cover_polygon(
+                &[(35.0, 139.0), (70.0, 139.0), (35.0, 140.0)],
+                MeshLevel::Lv3
+            ),
+            Err(JismeshError::LatitudeOutOfBounds {
+                value: 70.0,
+                index: 1,
+            })
+        );
+        assert_eq!(
+            cover_line(&[(35.0, 139.0), (35.0, 181.0)], MeshLevel::Lv3),
+            Err(JismeshError::LongitudeOutOfBounds {
+                value: 181.0,
+                index: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn test_cover_south_of_lv1_row_10() {
+        // Lv1 rows 0 to 9 have no valid mesh code, so their cells are skipped
+        let polygon = [(5.0, 139.0), (5.1, 139.0), (5.1, 139.1)];
+        assert!(cover_polygon(&polygon, MeshLevel::Lv3).unwrap().is_empty());
+        let line = [(5.001, 139.0), (5.001, 139.1)];
+        assert!(cover_line(&line, MeshLevel::Lv3).unwrap().is_empty());
+        assert!(
+            cover_circle(5.05, 139.05, 1000.0, MeshLevel::Lv3)
+                .unwrap()
+                .is_empty()
+        );
+
+        // A line crossing into Lv1 row 10 keeps the meshes north of it
+        let line = [(6.6, 139.05), (6.7, 139.05)];
+        let codes = cover_line(&line, MeshLevel::Lv1).unwrap();
+        assert_eq!(codes, [1039]);
+    }
+
+    #[test]
+    fn test_cover_circle_huge_radius() {
+        // The circle is clipped to the valid range, so every decodable Lv1 mesh is returned
+        for radius_m in [5e6, 1e9] {
+            let codes = cover_circle(35.0, 139.0, radius_m, MeshLevel::Lv1).unwrap();
+            assert!(!codes.is_empty());
+            assert!(codes.iter().all(|code| code.bounds().is_ok()));
+            assert!(codes.contains(&MeshCode::try_from(5339).unwrap()));
+        }
+        let codes = cover_circle(35.0, 139.0, 1e9, MeshLevel::Lv1).unwrap();
+        assert_eq!(codes.len(), 90 * 80);
+    }
+}
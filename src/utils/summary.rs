@@ -0,0 +1,150 @@
+use super::*;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// メッシュコード列の概要
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeshSummary {
+    /// メッシュコードの総数
+    pub count: usize,
+    /// 次数ごとのメッシュコード数
+    pub level_counts: HashMap<MeshLevel, usize>,
+    /// 全メッシュの中心点を囲む範囲 (南端の緯度, 西端の経度, 北端の緯度, 東端の経度)。
+    /// メッシュコードがない場合は None です。
+    pub centroid_bbox: Option<(f64, f64, f64, f64)>,
+    /// メッシュコードが含まれる 1次メッシュの数
+    pub lv1_count: usize,
+}
+
+/// Summarizes a column of mesh codes, for quick dataset profiling.
+///
+/// # Arguments
+/// * `codes` - The mesh codes to summarize. Levels may be mixed.
+///
+/// # Returns
+/// The number of codes per level, the bounding box of all centroids, and the number of
+/// distinct Lv1 meshes touched.
+///
+/// # Errors
+/// * Returns an error if the centroid of a code can't be calculated (e.g. 538000, whose
+///   longitude digits are out of range)
+pub fn summarize(codes: &[MeshCode]) -> Result<MeshSummary> {
+    let mut level_counts = HashMap::new();
+    let mut lv1 = HashSet::new();
+    let mut centroid_bbox: Option<(f64, f64, f64, f64)> = None;
+
+    for code in codes {
+        *level_counts.entry(code.level).or_insert(0) += 1;
+        lv1.insert(code.clamp_to_level(MeshLevel::Lv1)?);
+
+        let (lat, lon) = code.point(0.5, 0.5)?;
+        centroid_bbox = Some(match centroid_bbox {
+            None => (lat, lon, lat, lon),
+            Some((lat_s, lon_w, lat_n, lon_e)) => (
+                lat_s.min(lat),
+                lon_w.min(lon),
+                lat_n.max(lat),
+                lon_e.max(lon),
+            ),
+        });
+    }
+
+    Ok(MeshSummary {
+        count: codes.len(),
+        level_counts,
+        centroid_bbox,
+        lv1_count: lv1.len(),
+    })
+}
+
+/// Counts mesh codes per level.
+///
+/// # Arguments
+/// * `codes` - The mesh codes to count. Levels may be mixed.
+///
+/// # Returns
+/// The number of codes per level, ordered from the coarsest to the finest level. Levels
+/// without any codes are omitted.
+pub fn level_histogram(codes: &[MeshCode]) -> BTreeMap<MeshLevel, usize> {
+    let mut histogram = BTreeMap::new();
+    for code in codes {
+        *histogram.entry(code.level).or_insert(0) += 1;
+    }
+    histogram
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_summarize() {
+        let codes: Vec<MeshCode> = [53393599, 53393598, 533935, 5339359921, 52353680, 5339476]
+            .iter()
+            .map(|&v| MeshCode::try_from(v).unwrap())
+            .collect();
+        let summary = summarize(&codes).unwrap();
+
+        assert_eq!(summary.count, 6);
+        assert_eq!(summary.level_counts.len(), 4);
+        assert_eq!(summary.level_counts[&MeshLevel::Lv3], 3);
+        assert_eq!(summary.level_counts[&MeshLevel::Lv2], 1);
+        assert_eq!(summary.level_counts[&MeshLevel::Lv5], 1);
+        assert_eq!(summary.level_counts[&MeshLevel::X8], 1);
+        assert_eq!(summary.lv1_count, 2);
+
+        // The extremes are the Kyoto Lv3 mesh (south-west), a Tokyo Lv3 mesh (north) and the X8 mesh (east)
+        let (lat_s, lon_w, lat_n, lon_e) = summary.centroid_bbox.unwrap();
+        assert_relative_eq!(lat_s, 34.9875, epsilon = 1e-9);
+        assert_relative_eq!(lon_w, 135.75625, epsilon = 1e-9);
+        assert_relative_eq!(lat_n, 35.6625, epsilon = 1e-9);
+        assert_relative_eq!(lon_e, 139.75, epsilon = 1e-9);
+    }
+
+    #[test]
+    fn test_summarize_empty() {
+        let summary = summarize(&[]).unwrap();
+        assert_eq!(summary.count, 0);
+        assert!(summary.level_counts.is_empty());
+        assert_eq!(summary.centroid_bbox, None);
+        assert_eq!(summary.lv1_count, 0);
+    }
+
+    #[test]
+    fn test_summarize_undecodable() {
+        // 538000 has a valid Lv2 shape, but its longitude digits (80) are out of range
+        let codes = [
+            MeshCode::try_from(53393599).unwrap(),
+            MeshCode::try_from(538000).unwrap(),
+        ];
+        assert_eq!(
+            summarize(&codes),
+            Err(codes[1].point(0.5, 0.5).unwrap_err())
+        );
+        assert!(summarize(&codes[..1]).is_ok());
+    }
+
+    #[test]
+    fn test_level_histogram() {
+        let codes: Vec<MeshCode> = [53393599, 5339, 53393598, 533935, 5339359921, 5339476, 5235]
+            .iter()
+            .map(|&v| MeshCode::try_from(v).unwrap())
+            .collect();
+        let histogram = level_histogram(&codes);
+
+        // Ordered by physical size, coarsest first
+        let entries: Vec<(MeshLevel, usize)> = histogram.into_iter().collect();
+        assert_eq!(
+            entries,
+            vec![
+                (MeshLevel::Lv1, 2),
+                (MeshLevel::Lv2, 1),
+                (MeshLevel::X8, 1),
+                (MeshLevel::Lv3, 2),
+                (MeshLevel::Lv5, 1),
+            ]
+        );
+
+        assert!(level_histogram(&[]).is_empty());
+    }
+}